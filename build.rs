@@ -0,0 +1,70 @@
+//! Generates the list of solutions that are compiled into the main binary.
+//! With the `registry` feature, every `src/bin/NN.rs` and `src/bin/YYYY-NN.rs` is included as a module, so `cargo all` and `cargo time` can run them in-process.
+//! Without it, the list is empty and a day that doesn't compile only breaks its own binary.
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    println!("cargo:rerun-if-changed={}", bin_dir.display());
+
+    let mut days: Vec<String> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let name = entry.file_name().into_string().ok()?;
+                    let name = name.strip_suffix(".rs")?;
+                    is_solution(name).then(|| name.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    days.sort_unstable();
+
+    // NOTE: the registry is opt-in, and dhat builds define a global allocator per solution, which can't be linked into a single binary.
+    if env::var_os("CARGO_FEATURE_REGISTRY").is_none()
+        || env::var_os("CARGO_FEATURE_DHAT_HEAP").is_some()
+    {
+        days.clear();
+    }
+
+    let mut modules = String::new();
+    let mut calls = String::new();
+
+    for day in &days {
+        let path = bin_dir.join(format!("{day}.rs"));
+        let module = format!("day_{}", day.replace('-', "_"));
+        // solution tests already run as part of their own binary.
+        modules.push_str(&format!(
+            "#[cfg(not(test))]\n#[path = {:?}]\nmod {module};\n",
+            path.display().to_string()
+        ));
+        calls.push_str(&format!(
+            "    #[cfg(not(test))]\n    {module}::register(registry);\n"
+        ));
+    }
+
+    let generated = format!(
+        "{modules}\n\
+        /// Adds all solutions compiled into this binary to `registry`.\n\
+        #[allow(unused_variables)]\n\
+        pub fn register_all(registry: &mut advent_of_code::template::registry::Registry) {{\n\
+        {calls}}}\n"
+    );
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("solutions.rs");
+    fs::write(out_path, generated).unwrap();
+}
+
+/// Solutions are named after their day (`01`), or their year and day (`2021-01`).
+fn is_solution(name: &str) -> bool {
+    let is_number = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+
+    match name.split_once('-') {
+        Some((year, day)) => is_number(year, 4) && is_number(day, 2),
+        None => is_number(name, 2),
+    }
+}
@@ -1,19 +1,16 @@
+use advent_of_code::parse::{blocks, uints};
 use itertools::Itertools;
 
 advent_of_code::solution!(1);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    input
-        .split("\n\n")
-        .map(|elf| elf.lines().flat_map(str::parse::<u32>).sum())
-        .max()
+    blocks(input).map(|elf| uints::<u32>(elf).sum()).max()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     Some(
-        input
-            .split("\n\n")
-            .map(|elf| elf.lines().flat_map(str::parse::<u32>).sum::<u32>())
+        blocks(input)
+            .map(|elf| uints::<u32>(elf).sum::<u32>())
             .sorted()
             .rev()
             .take(3)
@@ -1,3 +1,8 @@
+use std::ops::Range;
+
+use advent_of_code::{interval::IntervalSet, parse::uints};
+use itertools::Itertools;
+
 advent_of_code::solution!(4);
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -5,9 +10,9 @@ pub fn part_one(input: &str) -> Option<u32> {
         input
             .lines()
             .filter(|line| {
-                let (left, right) = line.split_once(',').unwrap();
-                let (left, right) = (parse_range(left), parse_range(right));
-                right.0 >= left.0 && right.1 <= left.1 || left.0 >= right.0 && left.1 <= right.1
+                let (left, right) = parse_ranges(line);
+                IntervalSet::from(left.clone()).contains_range(&right)
+                    || IntervalSet::from(right).contains_range(&left)
             })
             .count() as u32,
     )
@@ -18,17 +23,19 @@ pub fn part_two(input: &str) -> Option<u32> {
         input
             .lines()
             .filter(|line| {
-                let (left, right) = line.split_once(',').unwrap();
-                let (left, right) = (parse_range(left), parse_range(right));
-                !(right.0 > left.1 || right.1 < left.0)
+                let (left, right) = parse_ranges(line);
+                IntervalSet::from(left).overlaps(&right)
             })
             .count() as u32,
     )
 }
 
-fn parse_range(s: &str) -> (u32, u32) {
-    let (start, end) = s.split_once('-').unwrap();
-    (start.parse().unwrap(), end.parse().unwrap())
+/// Parses a pair of inclusive section ranges like `2-4,6-8` into the half-open ranges `2..5` and `6..9`.
+fn parse_ranges(line: &str) -> (Range<u32>, Range<u32>) {
+    let (a, b, c, d) = uints(line)
+        .collect_tuple()
+        .expect("should be two section ranges");
+    (a..b + 1, c..d + 1)
 }
 
 #[cfg(test)]
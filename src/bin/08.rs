@@ -1,88 +1,82 @@
+use advent_of_code::grid::{Grid, Pos};
 use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
 
 advent_of_code::solution!(8);
 
 pub fn part_one(input: &str) -> Option<u32> {
     let heights = parse_heights(input);
-    let mut visible = vec![vec![false; heights[0].len()]; heights.len()];
-    for (row_idx, row) in heights.iter().enumerate() {
+    let mut visible = Grid::new(heights.width(), heights.height(), false);
+    for row_idx in 0..heights.height() {
+        let row = heights.row(row_idx);
         let mut max_from_left = -1;
         for (col_idx, &height) in row.iter().enumerate() {
             if height > max_from_left {
-                visible[row_idx][col_idx] = true;
+                visible[Pos::new(row_idx, col_idx)] = true;
                 max_from_left = height;
             }
         }
         let mut max_from_right = -1;
         for (col_idx, &height) in row.iter().enumerate().rev() {
             if height > max_from_right {
-                visible[row_idx][col_idx] = true;
+                visible[Pos::new(row_idx, col_idx)] = true;
                 max_from_right = height;
             }
         }
     }
-    for col_idx in 0..heights[0].len() {
+    for col_idx in 0..heights.width() {
         let mut max_from_top = -1;
-        for row_idx in 0..heights.len() {
-            let height = heights[row_idx][col_idx];
+        for (row_idx, &height) in heights.column(col_idx).enumerate() {
             if height > max_from_top {
-                visible[row_idx][col_idx] = true;
+                visible[Pos::new(row_idx, col_idx)] = true;
                 max_from_top = height;
             }
         }
         let mut max_from_bottom = -1;
-        for row_idx in (0..heights.len()).rev() {
-            let height = heights[row_idx][col_idx];
+        for (row_idx, &height) in heights.column(col_idx).enumerate().rev() {
             if height > max_from_bottom {
-                visible[row_idx][col_idx] = true;
+                visible[Pos::new(row_idx, col_idx)] = true;
                 max_from_bottom = height;
             }
         }
     }
-    Some(
-        visible
-            .into_iter()
-            .flat_map(|r| r.into_iter())
-            .filter(|&v| v)
-            .count() as u32,
-    )
+    Some(visible.values().filter(|&&v| v).count() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let heights = parse_heights(input);
-    (1..heights.len() - 1)
+    (1..heights.height() - 1)
         .into_par_iter()
-        .flat_map(|r| (1..heights[r].len() - 1).map(move |c| (r, c)).par_bridge())
-        .map(|(r, c)| visibility(&heights, r, c))
+        .flat_map(|r| {
+            (1..heights.width() - 1)
+                .map(move |c| Pos::new(r, c))
+                .par_bridge()
+        })
+        .map(|pos| visibility(&heights, pos))
         .max()
 }
 
-fn visibility(heights: &Vec<Vec<i8>>, row: usize, col: usize) -> u32 {
-    let height = heights[row][col];
+fn visibility(heights: &Grid<i8>, pos: Pos) -> u32 {
+    let Pos { row, col } = pos;
+    let height = heights[pos];
+    let row_heights = heights.row(row);
     let left_visibility = (1..=col)
-        .find(|o| heights[row][col - o] >= height)
+        .find(|o| row_heights[col - o] >= height)
         .unwrap_or(col) as u32;
-    let right_visibility = (1..=(heights[row].len() - col - 1))
-        .find(|o| heights[row][col + o] >= height)
-        .unwrap_or(heights[row].len() - col - 1) as u32;
+    let right_visibility = (1..=(heights.width() - col - 1))
+        .find(|o| row_heights[col + o] >= height)
+        .unwrap_or(heights.width() - col - 1) as u32;
     let up_visibility = (1..=row)
-        .find(|o| heights[row - o][col] >= height)
+        .find(|o| heights[Pos::new(row - o, col)] >= height)
         .unwrap_or(row) as u32;
-    let down_visibility = (1..=(heights.len() - row - 1))
-        .find(|o| heights[row + o][col] >= height)
-        .unwrap_or(heights.len() - row - 1) as u32;
+    let down_visibility = (1..=(heights.height() - row - 1))
+        .find(|o| heights[Pos::new(row + o, col)] >= height)
+        .unwrap_or(heights.height() - row - 1) as u32;
     left_visibility * right_visibility * up_visibility * down_visibility
 }
 
-fn parse_heights(input: &str) -> Vec<Vec<i8>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|ch| ch.to_digit(10).expect("a digit") as i8)
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>()
+fn parse_heights(input: &str) -> Grid<i8> {
+    Grid::parse(input, |_, ch| ch.to_digit(10).expect("a digit") as i8)
+        .expect("input should be a rectangular map")
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use advent_of_code::geom::{Dir4, Point2};
+
 advent_of_code::solution!(9);
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -23,13 +25,15 @@ fn solve<const N: usize>(input: &str) -> Option<u32> {
     Some(visited.len() as u32)
 }
 
+type Position = Point2<i32>;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 struct Snake<const N: usize> {
     knots: [Position; N],
 }
 
 impl<const N: usize> Snake<N> {
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             knots: [Position::origin(); N],
         }
@@ -39,73 +43,35 @@ impl<const N: usize> Snake<N> {
         self.knots[N - 1]
     }
 
-    pub fn do_step(&mut self, direction: Direction) {
-        self.knots[0] = self.knots[0].go(direction);
+    pub fn do_step(&mut self, direction: Dir4) {
+        self.knots[0] += direction;
         for i in 1..N {
-            self.knots[i] = self.knots[i].follow(self.knots[i - 1]);
+            self.knots[i] = follow(self.knots[i], self.knots[i - 1]);
         }
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Position {
-    const fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-
-    const fn origin() -> Self {
-        Self { x: 0, y: 0 }
-    }
-
-    const fn go(self, dir: Direction) -> Self {
-        match dir {
-            Direction::Up => Self::new(self.x, self.y + 1),
-            Direction::Down => Self::new(self.x, self.y - 1),
-            Direction::Left => Self::new(self.x - 1, self.y),
-            Direction::Right => Self::new(self.x + 1, self.y),
-        }
-    }
-
-    const fn follow(self, head: Self) -> Self {
-        let x_diff = head.x - self.x;
-        let y_diff = head.y - self.y;
-        if x_diff.abs() <= 1 && y_diff.abs() <= 1 {
-            return self;
-        }
-        Self {
-            x: self.x + x_diff.signum(),
-            y: self.y + y_diff.signum(),
-        }
+fn follow(knot: Position, head: Position) -> Position {
+    if knot.chebyshev(head) <= 1 {
+        return knot;
     }
+    knot + (head - knot).signum()
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 struct Motion {
     steps: u8,
-    direction: Direction,
+    direction: Dir4,
 }
 
 impl Motion {
     fn parse(line: &str) -> Self {
         let (direction, steps) = line.split_once(' ').expect("should be a valid motion");
         let direction = match direction {
-            "U" => Direction::Up,
-            "D" => Direction::Down,
-            "L" => Direction::Left,
-            "R" => Direction::Right,
+            "U" => Dir4::Up,
+            "D" => Dir4::Down,
+            "L" => Dir4::Left,
+            "R" => Dir4::Right,
             _ => unreachable!(),
         };
         let steps = steps
@@ -1,3 +1,4 @@
+use advent_of_code::parse::ints;
 use itertools::Itertools;
 
 advent_of_code::solution!(10);
@@ -10,7 +11,7 @@ pub fn part_one(input: &str) -> Option<i32> {
         if cycle == 20 || cycle > 20 && (cycle - 20) % 40 == 0 {
             signal_strength_sum += cycle * x;
         }
-        let noop = line.starts_with(|ch| ch == 'n');
+        let noop = line.starts_with('n');
         cycle += 1;
         if noop {
             continue;
@@ -18,7 +19,7 @@ pub fn part_one(input: &str) -> Option<i32> {
         if cycle == 20 || cycle > 20 && (cycle - 20) % 40 == 0 {
             signal_strength_sum += cycle * x;
         }
-        let v = line[5..].parse::<i32>().expect("valid instruction");
+        let v = ints::<i32>(line).next().expect("valid instruction");
         cycle += 1;
         x += v;
     }
@@ -30,12 +31,12 @@ pub fn part_two(input: &str) -> Option<String> {
     let mut x = 1;
     for line in input.lines() {
         crt.do_cycle(x);
-        let noop = line.starts_with(|ch| ch == 'n');
+        let noop = line.starts_with('n');
         if noop {
             continue;
         }
         crt.do_cycle(x);
-        let v = line[5..].parse::<i32>().expect("valid instruction");
+        let v = ints::<i32>(line).next().expect("valid instruction");
         x += v;
     }
     Some(crt.draw())
@@ -1,11 +1,12 @@
 use std::collections::VecDeque;
 
+use advent_of_code::parse::{blocks, uints};
 use itertools::Itertools;
 
 advent_of_code::solution!(11);
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let mut monkeys = input.split("\n\n").map(parse_monkey).collect::<Vec<_>>();
+    let mut monkeys = blocks(input).map(parse_monkey).collect::<Vec<_>>();
     for _ in 0..20 {
         for i in 0..monkeys.len() {
             while let Some((to, value)) = monkeys[i].inspect_one() {
@@ -25,7 +26,7 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let mut monkeys = input.split("\n\n").map(parse_monkey).collect::<Vec<_>>();
+    let mut monkeys = blocks(input).map(parse_monkey).collect::<Vec<_>>();
     let modulo = monkeys.iter().map(|monkey| monkey.divisibility).product();
     for _ in 0..10_000 {
         for i in 0..monkeys.len() {
@@ -47,19 +48,12 @@ pub fn part_two(input: &str) -> Option<u64> {
 
 fn parse_monkey(input: &str) -> Monkey {
     let mut lines = input.lines().skip(1);
-    let starting_items = lines.next().unwrap();
-    let (_, starting_items) = starting_items.split_once(':').unwrap();
-    let starting_items = starting_items
-        .trim()
-        .split(", ")
-        .map(str::parse)
-        .collect::<Result<VecDeque<u64>, _>>()
-        .unwrap();
+    let starting_items = uints(lines.next().unwrap()).collect::<VecDeque<u64>>();
     let operation = lines.next().unwrap();
     let (_, operation) = operation.split_once(" = ").unwrap();
-    let divisibility = lines.next().unwrap()[21..].parse::<u64>().unwrap();
-    let if_true = lines.next().unwrap()[29..].parse::<usize>().unwrap();
-    let if_false = lines.next().unwrap()[30..].parse::<usize>().unwrap();
+    let divisibility = uints::<u64>(lines.next().unwrap()).next().unwrap();
+    let if_true = uints::<usize>(lines.next().unwrap()).next().unwrap();
+    let if_false = uints::<usize>(lines.next().unwrap()).next().unwrap();
 
     Monkey {
         items: starting_items,
@@ -129,7 +123,7 @@ impl Monkey {
         let front = self.items.pop_front()?;
         let post_inspected = self.operation.apply(front) / 3;
         self.inspected_count += 1;
-        let divisible = post_inspected % self.divisibility == 0;
+        let divisible = post_inspected.is_multiple_of(self.divisibility);
         if divisible {
             Some((self.if_true, post_inspected))
         } else {
@@ -141,7 +135,7 @@ impl Monkey {
         let front = self.items.pop_front()?;
         let post_inspected = self.operation.apply(front) % modulo;
         self.inspected_count += 1;
-        let divisible = post_inspected % self.divisibility == 0;
+        let divisible = post_inspected.is_multiple_of(self.divisibility);
         if divisible {
             Some((self.if_true, post_inspected))
         } else {
@@ -126,10 +126,10 @@ impl From<u8> for Entry {
 
 impl<T> From<&T> for Entry
 where
-    T: Into<Entry>,
+    T: Clone + Into<Entry>,
 {
     fn from(value: &T) -> Self {
-        value.into()
+        value.clone().into()
     }
 }
 
@@ -144,7 +144,7 @@ where
 
 impl<T> From<&[T]> for Entry
 where
-    T: Into<Entry>,
+    T: Clone + Into<Entry>,
 {
     fn from(entrys: &[T]) -> Self {
         Entry::List(entrys.iter().map(|e| e.into()).collect())
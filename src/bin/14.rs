@@ -1,7 +1,12 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fmt::{Display, Write},
+use std::fmt::{Display, Write};
+
+use advent_of_code::{
+    geom::{Dir8, Point2},
+    grid::SparseGrid,
+    parse::ints,
+    search::bfs_reach,
 };
+use itertools::Itertools;
 
 advent_of_code::solution!(14);
 
@@ -17,35 +22,32 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     let mut map = Map::parse(input);
     map.use_floor = true;
-    Some(map.bfs())
+    Some(map.reachable())
 }
 
 #[derive(Debug, Clone, Default)]
 struct Map {
-    objects: HashMap<Coordinate, Object>,
-    max_y: u16,
+    objects: SparseGrid<Object>,
+    max_y: i64,
     use_floor: bool,
     prev_before_last_settle: Option<Coordinate>,
 }
 
 impl Map {
     pub fn parse(input: &str) -> Self {
-        let mut objects = HashMap::new();
+        let mut objects = SparseGrid::new();
         let mut max_y = 0;
         for line in input.lines() {
             let mut prev: Option<Coordinate> = None;
-            for coord in line.split(" -> ") {
-                let (x, y) = coord.split_once(',').expect("should be \"x,y\"");
-                let x = x.parse::<u16>().expect("valid x coordinate");
-                let y = y.parse::<u16>().expect("valid y coordinate");
+            for (x, y) in ints::<i64>(line).tuples() {
                 if y > max_y {
                     max_y = y;
                 }
                 let cur = Coordinate::new(x, y);
                 if let Some(prev) = prev {
-                    prev.line_to(cur, |coord| {
+                    for coord in prev.line_to(cur) {
                         objects.insert(coord, Object::Rock);
-                    });
+                    }
                 }
                 prev = Some(cur);
             }
@@ -82,45 +84,30 @@ impl Map {
     }
 
     pub fn next_available_spot(&self, coord: Coordinate) -> Option<Coordinate> {
-        if let Some(next) = Some(coord.down()).filter(|&c| !self.blocked(c)) {
-            return Some(next);
-        }
-        if let Some(next) = Some(coord.down_left()).filter(|&c| !self.blocked(c)) {
-            return Some(next);
-        }
-        if let Some(next) = Some(coord.down_right()).filter(|&c| !self.blocked(c)) {
-            return Some(next);
-        }
-        None
+        FALL_DIRECTIONS
+            .into_iter()
+            .map(|dir| coord + dir)
+            .find(|&c| !self.blocked(c))
     }
 
     fn blocked(&self, coord: Coordinate) -> bool {
-        self.objects.contains_key(&coord) || self.use_floor && coord.y >= self.max_y + 2
+        self.objects.contains(coord) || self.use_floor && coord.y >= self.max_y + 2
     }
 
-    pub fn bfs(&self) -> u32 {
-        let origin = Coordinate::new(500, 0);
-        let mut visited = HashSet::new();
-        let mut open = VecDeque::new();
-        visited.insert(origin);
-        open.push_back(origin);
-        let mut count = 0;
-        while let Some(cur) = open.pop_front() {
-            count += 1;
-            for next in [cur.down(), cur.down_left(), cur.down_right()]
+    /// Counts the positions sand can reach, i.e. the sand that settles until the source is blocked.
+    pub fn reachable(&self) -> u32 {
+        let floor_y = if self.use_floor {
+            self.max_y + 2
+        } else {
+            self.max_y
+        };
+        let tree = bfs_reach([Coordinate::new(500, 0)], |&cur| {
+            FALL_DIRECTIONS
                 .into_iter()
-                .filter(|&c| {
-                    !self.blocked(c)
-                        && (self.use_floor && c.y < self.max_y + 2
-                            || !self.use_floor && c.y < self.max_y)
-                })
-            {
-                if visited.insert(next) {
-                    open.push_back(next);
-                }
-            }
-        }
-        count
+                .map(move |dir| cur + dir)
+                .filter(|&c| !self.blocked(c) && c.y < floor_y)
+        });
+        tree.len() as u32
     }
 }
 
@@ -130,73 +117,21 @@ enum Object {
     Sand,
 }
 
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
-struct Coordinate {
-    x: u16,
-    y: u16,
-}
-
-impl Coordinate {
-    pub const fn new(x: u16, y: u16) -> Self {
-        Self { x, y }
-    }
-
-    pub fn line_to<F: FnMut(Self)>(self, other: Self, mut f: F) {
-        if self.x == other.x {
-            let from = self.y.min(other.y);
-            let to = self.y.max(other.y);
-            for y in from..=to {
-                f(Self::new(self.x, y));
-            }
-        } else if self.y == other.y {
-            let from = self.x.min(other.x);
-            let to = self.x.max(other.x);
-            for x in from..=to {
-                f(Self::new(x, self.y));
-            }
-        } else {
-            unreachable!("can't do a horizontal or vertical line when neither coordinate matches");
-        }
-    }
-
-    pub const fn down(self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-
-    pub const fn down_left(self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y + 1,
-        }
-    }
+type Coordinate = Point2<i64>;
 
-    pub const fn down_right(self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y + 1,
-        }
-    }
-}
+/// Directions in which sand tries to fall, in order.
+const FALL_DIRECTIONS: [Dir8; 3] = [Dir8::S, Dir8::SW, Dir8::SE];
 
 impl Display for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut min_x = 500;
-        let mut max_x = 500;
-        for key in self.objects.keys() {
-            if key.x < min_x {
-                min_x = key.x;
-            }
-            if key.x > max_x {
-                max_x = key.x;
-            }
-        }
+        let (min_x, max_x) = self
+            .objects
+            .bounds()
+            .map_or((500, 500), |(min, max)| (min.x.min(500), max.x.max(500)));
         for y in 0..=self.max_y {
             for x in min_x..=max_x {
                 let coord = Coordinate::new(x, y);
-                let ch = match (coord, self.objects.get(&coord)) {
+                let ch = match (coord, self.objects.get(coord)) {
                     (Coordinate { x, y }, _) if x == 500 && y == 0 => '+',
                     (_, None) => '.',
                     (_, Some(Object::Rock)) => '#',
@@ -5,77 +5,124 @@ use std::{
     str::FromStr,
 };
 
+use advent_of_code::{
+    search::bfs_reach,
+    template::{FromInput, LineContext, LineError},
+};
 use thiserror::Error;
 
-advent_of_code::solution!(16);
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let network = input.parse::<Network>().expect("input should be valid");
-    Some(part_one_recursive(
-        &network,
-        Valve(0),
-        30,
-        0,
-        &mut HashMap::new(),
-    ))
+advent_of_code::solution!(16, parser = Network::from_input);
+
+pub fn part_one(network: &Network) -> Option<u32> {
+    network.best_pressures(30).into_iter().max()
 }
 
-fn part_one_recursive(
-    network: &Network,
-    valve: Valve,
-    time_remaining: u32,
-    closed_valves: u64,
-    cache: &mut HashMap<(Valve, u32, u64), u32>,
-) -> u32 {
-    if time_remaining <= 1 {
-        return 0;
-    }
-    if let Some(&cached) = cache.get(&(valve, time_remaining, closed_valves)) {
-        return cached;
-    }
-    let mut max = 0;
-    let flow = network.flow_rates[&valve];
-    let valve_mask = 1u64 << network.valve_indexes[&valve];
-    if (closed_valves & valve_mask) == 0 && flow > 0 {
-        max = (time_remaining - 1) * flow
-            + part_one_recursive(
-                network,
-                valve,
-                time_remaining - 1,
-                closed_valves | valve_mask,
-                cache,
-            );
-    }
-    for &neighbor in &network.adjacency[&valve] {
-        max = max.max(part_one_recursive(
-            network,
-            neighbor,
-            time_remaining - 1,
-            closed_valves,
-            cache,
-        ));
+pub fn part_two(network: &Network) -> Option<u32> {
+    let mut best = network.best_pressures(26);
+
+    // Let every set of valves hold the best pressure of any of its subsets,
+    // so the best set disjoint from the one opened by us is simply its complement.
+    for bit in (0..network.flow_rates.len()).map(|i| 1 << i) {
+        for opened in 0..best.len() {
+            if opened & bit != 0 {
+                best[opened] = best[opened].max(best[opened ^ bit]);
+            }
+        }
     }
-    cache.insert((valve, time_remaining, closed_valves), max);
-    max
-}
 
-#[allow(unused)]
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+    let all = best.len() - 1;
+    (0..best.len())
+        .map(|opened| best[opened] + best[all ^ opened])
+        .max()
 }
 
+/// The valves with a non-zero flow rate and the start valve, with the time it takes to travel between any two of them.
+/// Valves are identified by their index, sets of valves by a bitmask of their indices.
 #[derive(Debug, Clone)]
-struct Network {
-    adjacency: HashMap<Valve, Vec<Valve>>,
-    flow_rates: HashMap<Valve, u32>,
-    valve_indexes: HashMap<Valve, u8>,
+pub struct Network {
+    /// Flow rates of the valves with a non-zero flow rate.
+    flow_rates: Vec<u32>,
+    /// Row-major matrix of the minutes it takes to travel between two valves.
+    /// The start valve has the index after the last valve with a flow rate.
+    travel_times: Vec<u32>,
+}
+
+impl Network {
+    /// Reduces the tunnels between all valves to the travel times between the valves worth opening.
+    fn new(tunnels: &HashMap<Valve, Vec<Valve>>, flow_rates: &HashMap<Valve, u32>) -> Self {
+        let mut valves = flow_rates
+            .iter()
+            .filter(|&(_, &flow_rate)| flow_rate > 0)
+            .map(|(&valve, _)| valve)
+            .collect::<Vec<_>>();
+        valves.sort_unstable_by_key(|valve| valve.0);
+        let flow_rates = valves.iter().map(|valve| flow_rates[valve]).collect();
+
+        valves.push(START);
+        let travel_times = valves
+            .iter()
+            .flat_map(|&from| {
+                let reachable = bfs_reach([from], |valve| {
+                    tunnels.get(valve).into_iter().flatten().copied()
+                });
+                valves.iter().map(move |to| {
+                    reachable
+                        .cost(to)
+                        .map_or(u32::MAX, |minutes| minutes as u32)
+                })
+            })
+            .collect();
+
+        Self {
+            flow_rates,
+            travel_times,
+        }
+    }
+
+    fn travel_time(&self, from: usize, to: usize) -> u32 {
+        self.travel_times[from * (self.flow_rates.len() + 1) + to]
+    }
+
+    /// Returns the most pressure that can be released within `minutes` by opening exactly the valves of a set,
+    /// indexed by the bitmask of the set.
+    fn best_pressures(&self, minutes: u32) -> Vec<u32> {
+        let mut best = vec![0; 1 << self.flow_rates.len()];
+        self.open_valves(self.flow_rates.len(), minutes, 0, 0, &mut best);
+        best
+    }
+
+    fn open_valves(
+        &self,
+        valve: usize,
+        minutes_left: u32,
+        opened: usize,
+        pressure: u32,
+        best: &mut [u32],
+    ) {
+        best[opened] = best[opened].max(pressure);
+        for (next, &flow_rate) in self.flow_rates.iter().enumerate() {
+            let minutes = self.travel_time(valve, next).saturating_add(1);
+            if opened & (1 << next) == 0 && minutes < minutes_left {
+                let minutes_left = minutes_left - minutes;
+                self.open_valves(
+                    next,
+                    minutes_left,
+                    opened | (1 << next),
+                    pressure + minutes_left * flow_rate,
+                    best,
+                );
+            }
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 struct Valve(u16);
 
+const START: Valve = Valve(0);
+
 #[derive(Error, Debug)]
-enum ParseNetworkError {
+pub enum ParseNetworkError {
     #[error("could not parse valve line")]
     GenericParseError,
 
@@ -86,43 +133,46 @@ enum ParseNetworkError {
     InvalidFlowRate(#[from] ParseIntError),
 }
 
-impl FromStr for Network {
-    type Err = ParseNetworkError;
+impl FromInput for Network {
+    type Error = LineError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut adjacency = HashMap::new();
+    fn from_input(input: &str) -> Result<Self, Self::Error> {
+        let mut tunnels = HashMap::new();
         let mut flow_rates = HashMap::new();
-        let mut valve_indexes = HashMap::new();
-        for (line_idx, line) in s.lines().enumerate() {
-            let (valve, tunnels) = line
-                .split_once("; ")
-                .ok_or(ParseNetworkError::GenericParseError)?;
-            let (valve_id, flow_rate) = valve
-                .split_once('=')
-                .ok_or(ParseNetworkError::GenericParseError)?;
-            let valve_id = valve_id[6..=7].parse()?;
-            let flow_rate = flow_rate.parse()?;
-            let idx = tunnels
-                .find(|ch: char| ch.is_ascii_uppercase())
-                .ok_or(ParseNetworkError::GenericParseError)?;
-            let tunnels = tunnels[idx..]
-                .split(", ")
-                .map(|s| s.parse())
-                .collect::<Result<_, _>>()?;
-            adjacency.insert(valve_id, tunnels);
+        for (line_idx, line) in input.lines().enumerate() {
+            let (valve_id, flow_rate, valve_tunnels) =
+                parse_valve_line(line).line_context(line_idx, line)?;
+            tunnels.insert(valve_id, valve_tunnels);
             flow_rates.insert(valve_id, flow_rate);
-            valve_indexes.insert(valve_id, line_idx as u8);
         }
-        Ok(Self {
-            adjacency,
-            flow_rates,
-            valve_indexes,
-        })
+        Ok(Self::new(&tunnels, &flow_rates))
     }
 }
 
+fn parse_valve_line(line: &str) -> Result<(Valve, u32, Vec<Valve>), ParseNetworkError> {
+    let (valve, tunnels) = line
+        .split_once("; ")
+        .ok_or(ParseNetworkError::GenericParseError)?;
+    let (valve_id, flow_rate) = valve
+        .split_once('=')
+        .ok_or(ParseNetworkError::GenericParseError)?;
+    let valve_id = valve_id
+        .get(6..=7)
+        .ok_or(ParseNetworkError::GenericParseError)?
+        .parse()?;
+    let flow_rate = flow_rate.parse()?;
+    let idx = tunnels
+        .find(|ch: char| ch.is_ascii_uppercase())
+        .ok_or(ParseNetworkError::GenericParseError)?;
+    let tunnels = tunnels[idx..]
+        .split(", ")
+        .map(|s| s.parse())
+        .collect::<Result<_, _>>()?;
+    Ok((valve_id, flow_rate, tunnels))
+}
+
 #[derive(Error, Debug)]
-enum ParseValveError {
+pub enum ParseValveError {
     #[error("invalid valve id character: {0}")]
     InvalidCharacter(char),
 
@@ -168,13 +218,17 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let network =
+            Network::from_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let result = part_one(&network);
         assert_eq!(result, Some(1651));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let network =
+            Network::from_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let result = part_two(&network);
         assert_eq!(result, Some(1707));
     }
 }
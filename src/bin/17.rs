@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+advent_of_code::solution!(17);
+
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(tower_height(&parse_jets(input), 2022))
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    Some(tower_height(&parse_jets(input), 1_000_000_000_000))
+}
+
+/// The rocks in their starting position, two units away from the left wall.
+/// Every byte is a row, starting with the bottom one. Bit 6 is the leftmost column, bit 0 the rightmost.
+const ROCKS: [u32; 5] = [
+    0x0000_001E, // -
+    0x0008_1C08, // +
+    0x0004_041C, // ⅃
+    0x1010_1010, // |
+    0x0000_1818, // ■
+];
+const LEFT_WALL: u32 = 0x4040_4040;
+const RIGHT_WALL: u32 = 0x0101_0101;
+
+/// Number of rows at the top of the tower that are compared to find a repeating state.
+const SURFACE_ROWS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Jet {
+    Left,
+    Right,
+}
+
+fn parse_jets(input: &str) -> Vec<Jet> {
+    input
+        .trim()
+        .bytes()
+        .map(|b| match b {
+            b'<' => Jet::Left,
+            b'>' => Jet::Right,
+            _ => panic!("invalid jet {:?}", b as char),
+        })
+        .collect()
+}
+
+/// Returns the height of the tower after `rocks` rocks have fallen.
+///
+/// Once the top of the tower, the next rock and the next jet repeat, every cycle adds the same height,
+/// so all complete cycles are skipped and only the remaining rocks are simulated.
+fn tower_height(jets: &[Jet], rocks: u64) -> u64 {
+    let mut chamber = Chamber::new(jets);
+    let mut seen = HashMap::new();
+    let mut skipped_height = 0;
+    let mut dropped = 0;
+
+    while dropped < rocks {
+        chamber.drop_rock();
+        dropped += 1;
+
+        if skipped_height > 0 {
+            continue;
+        }
+        if let Some(key) = chamber.state() {
+            if let Some((previous_dropped, previous_height)) =
+                seen.insert(key, (dropped, chamber.height()))
+            {
+                let cycle_rocks = dropped - previous_dropped;
+                let cycles = (rocks - dropped) / cycle_rocks;
+                skipped_height = cycles * (chamber.height() - previous_height);
+                dropped += cycles * cycle_rocks;
+            }
+        }
+    }
+
+    chamber.height() + skipped_height
+}
+
+struct Chamber<'a> {
+    /// The settled rocks, one byte per row from the floor up. Only rows with rocks are stored.
+    rows: Vec<u8>,
+    jets: &'a [Jet],
+    next_jet: usize,
+    next_rock: usize,
+}
+
+impl<'a> Chamber<'a> {
+    fn new(jets: &'a [Jet]) -> Self {
+        Self {
+            rows: vec![],
+            jets,
+            next_jet: 0,
+            next_rock: 0,
+        }
+    }
+
+    fn height(&self) -> u64 {
+        self.rows.len() as u64
+    }
+
+    /// Returns the four rows starting at `y` packed like a rock, rows above the tower are empty.
+    fn rows_at(&self, y: usize) -> u32 {
+        self.rows
+            .iter()
+            .skip(y)
+            .take(4)
+            .rev()
+            .fold(0, |packed, &row| packed << 8 | u32::from(row))
+    }
+
+    fn collides(&self, rock: u32, y: usize) -> bool {
+        rock & self.rows_at(y) != 0
+    }
+
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.next_rock];
+        self.next_rock = (self.next_rock + 1) % ROCKS.len();
+        let mut y = self.rows.len() + 3;
+
+        loop {
+            let pushed = match self.jets[self.next_jet] {
+                Jet::Left if rock & LEFT_WALL == 0 => rock << 1,
+                Jet::Right if rock & RIGHT_WALL == 0 => rock >> 1,
+                _ => rock,
+            };
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            if !self.collides(pushed, y) {
+                rock = pushed;
+            }
+
+            if y == 0 || self.collides(rock, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for (i, row) in rock.to_le_bytes().into_iter().enumerate() {
+            if row == 0 {
+                break;
+            }
+            if y + i == self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[y + i] |= row;
+        }
+    }
+
+    /// Returns what determines how the tower continues to grow: the next rock, the next jet and the top rows.
+    /// `None` while the tower is lower than the compared rows.
+    fn state(&self) -> Option<(usize, usize, [u8; SURFACE_ROWS])> {
+        let top = self.rows.len().checked_sub(SURFACE_ROWS)?;
+        let surface = self.rows[top..].try_into().ok()?;
+        Some((self.next_rock, self.next_jet, surface))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", PUZZLE));
+        assert_eq!(result, Some(3068));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", PUZZLE));
+        assert_eq!(result, Some(1_514_285_714_288));
+    }
+
+    #[test]
+    fn test_cycle_skipping_matches_simulation() {
+        let jets = parse_jets(&advent_of_code::template::read_file("examples", PUZZLE));
+        let mut chamber = Chamber::new(&jets);
+        for rocks in 1..=5000 {
+            chamber.drop_rock();
+            assert_eq!(tower_height(&jets, rocks), chamber.height());
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use advent_of_code::{geom::Point3, parse::ints};
+use itertools::Itertools;
+
+advent_of_code::solution!(18);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let cubes = parse_cubes(input);
+    let lava = Voxels::padded_around(&cubes);
+    Some(
+        cubes
+            .iter()
+            .flat_map(|cube| cube.neighbors6())
+            .filter(|&neighbor| !lava.contains(neighbor))
+            .count(),
+    )
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let lava = Voxels::padded_around(&parse_cubes(input));
+
+    // The padding around the droplet connects all of the outside, so a flood fill from a corner of the box reaches
+    // every exterior cube. Every lava cube next to it is a face on the outer surface.
+    let mut exterior = lava.empty_copy();
+    let mut stack = vec![lava.min];
+    exterior.insert(lava.min);
+    let mut faces = 0;
+
+    while let Some(cube) = stack.pop() {
+        for neighbor in cube.neighbors6() {
+            if lava.contains(neighbor) {
+                faces += 1;
+            } else if exterior.in_bounds(neighbor) && exterior.insert(neighbor) {
+                stack.push(neighbor);
+            }
+        }
+    }
+
+    Some(faces)
+}
+
+type Cube = Point3<i32>;
+
+fn parse_cubes(input: &str) -> Vec<Cube> {
+    input
+        .lines()
+        .map(|line| {
+            let (x, y, z) = ints(line)
+                .collect_tuple()
+                .expect("should be a cube like \"1,2,3\"");
+            Cube::new(x, y, z)
+        })
+        .collect()
+}
+
+/// A set of unit cubes within a box, stored as one bit per cube.
+struct Voxels {
+    /// The corner of the box with the lowest coordinates.
+    min: Cube,
+    /// Number of cubes along every axis.
+    size: Cube,
+    bits: Vec<u64>,
+}
+
+impl Voxels {
+    fn empty(min: Cube, size: Cube) -> Self {
+        let len = (size.x * size.y * size.z) as usize;
+        Self {
+            min,
+            size,
+            bits: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// Returns a set of `cubes` in a box that leaves a layer of empty cubes around them.
+    fn padded_around(cubes: &[Cube]) -> Self {
+        let min = cubes
+            .iter()
+            .copied()
+            .reduce(|a, b| Cube::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)))
+            .unwrap_or_default();
+        let max = cubes
+            .iter()
+            .copied()
+            .reduce(|a, b| Cube::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)))
+            .unwrap_or_default();
+
+        let one = Cube::new(1, 1, 1);
+        let mut voxels = Self::empty(min - one, max - min + one * 3);
+        for &cube in cubes {
+            voxels.insert(cube);
+        }
+        voxels
+    }
+
+    /// Returns an empty set with the same box.
+    fn empty_copy(&self) -> Self {
+        Self::empty(self.min, self.size)
+    }
+
+    fn index(&self, cube: Cube) -> Option<usize> {
+        let Cube { x, y, z } = cube - self.min;
+        let in_bounds = (0..self.size.x).contains(&x)
+            && (0..self.size.y).contains(&y)
+            && (0..self.size.z).contains(&z);
+        in_bounds.then(|| ((z * self.size.y + y) * self.size.x + x) as usize)
+    }
+
+    fn in_bounds(&self, cube: Cube) -> bool {
+        self.index(cube).is_some()
+    }
+
+    fn contains(&self, cube: Cube) -> bool {
+        self.index(cube)
+            .is_some_and(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    /// Adds `cube` to the set, returns whether it was not in the set before.
+    ///
+    /// # Panics
+    /// If `cube` is outside of the box.
+    fn insert(&mut self, cube: Cube) -> bool {
+        let i = self.index(cube).expect("cube should be inside the box");
+        let was_set = self.bits[i / 64] & (1 << (i % 64)) != 0;
+        self.bits[i / 64] |= 1 << (i % 64);
+        !was_set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", PUZZLE));
+        assert_eq!(result, Some(64));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", PUZZLE));
+        assert_eq!(result, Some(58));
+    }
+
+    #[test]
+    fn test_two_adjacent_cubes() {
+        assert_eq!(part_one("1,1,1\n2,1,1"), Some(10));
+        assert_eq!(part_two("1,1,1\n2,1,1"), Some(10));
+    }
+}
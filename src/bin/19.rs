@@ -1,3 +1,4 @@
+use advent_of_code::dfs::branch_and_bound;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 
 advent_of_code::solution!(19);
@@ -12,9 +13,7 @@ pub fn part_one(input: &str) -> Option<u32> {
         .enumerate()
         .par_bridge()
         .map(|(id, line)| (id as u32 + 1, line.into()))
-        .map(|(id, blueprint)| {
-            id * search_recursive(blueprint, starting_state, 24, 0, [false; 4], [false; 4])
-        })
+        .map(|(id, blueprint)| id * search(blueprint, starting_state, 24))
         .sum();
     Some(quality_sum)
 }
@@ -29,96 +28,126 @@ pub fn part_two(input: &str) -> Option<u32> {
         .take(3)
         .par_bridge()
         .map(|line| line.into())
-        .map(|blueprint| search_recursive(blueprint, starting_state, 32, 0, [false; 4], [false; 4]))
+        .map(|blueprint| search(blueprint, starting_state, 32))
         .product::<u32>();
     Some(product)
 }
 
-fn search_recursive(
-    blueprint: Blueprint,
-    state: RobotState,
+fn search(blueprint: Blueprint, state: RobotState, minutes: u16) -> u32 {
+    let start = SearchState {
+        robots: state,
+        minutes_left: minutes,
+        last_buy: [false; 4],
+        last_affordable: [false; 4],
+    };
+    let (max_geodes, _) = branch_and_bound(
+        start,
+        |search_state| search_state.next_states(blueprint),
+        // Building nothing until the end still collects the geodes of the current geode robots.
+        |search_state| {
+            let SearchState {
+                robots,
+                minutes_left,
+                ..
+            } = *search_state;
+            (robots.geodes + robots.geode_robots * minutes_left) as u32
+        },
+        // If we were to build a geode robot every turn until the end, see if we can beat the best so far.
+        |search_state| {
+            let SearchState {
+                robots,
+                minutes_left,
+                ..
+            } = *search_state;
+            (minutes_left * minutes_left.saturating_sub(1) / 2
+                + robots.geodes
+                + robots.geode_robots * minutes_left) as u32
+        },
+    );
+    max_geodes
+}
+
+#[derive(Debug, Copy, Clone)]
+struct SearchState {
+    robots: RobotState,
     minutes_left: u16,
-    best_so_far: u32,
     last_buy: [bool; 4],
     last_affordable: [bool; 4],
-) -> u32 {
-    if minutes_left == 0 {
-        return state.geodes as u32;
-    }
+}
 
-    // If we were to build a geode robot every turn until the end, see if we can beat the best so far.
-    let max_possible_geodes = (minutes_left * (minutes_left - 1) / 2
-        + state.geodes
-        + state.geode_robots * minutes_left) as u32;
-    if max_possible_geodes < best_so_far {
-        return 0;
-    }
+impl SearchState {
+    fn next_states(&self, blueprint: Blueprint) -> Vec<Self> {
+        let Self {
+            robots: state,
+            minutes_left,
+            last_buy,
+            last_affordable,
+        } = *self;
+        let mut next_states = vec![];
+        if minutes_left == 0 {
+            return next_states;
+        }
 
-    // First try buying things.
-    let mut max = best_so_far;
-    let bots_affordable = state.bots_affordable(blueprint);
-    let bot_costs = [
-        blueprint.ore_robot,
-        blueprint.clay_robot,
-        blueprint.obsidian_robot,
-        blueprint.geode_robot,
-    ];
-    let current_bots = [
-        state.ore_robots,
-        state.clay_robots,
-        state.obsidian_robots,
-        state.geode_robots,
-    ];
-    let mut max_resource_costs = [0; 3];
-    for costs in bot_costs {
-        max_resource_costs[0] = max_resource_costs[0].max(costs.ore);
-        max_resource_costs[1] = max_resource_costs[1].max(costs.clay);
-        max_resource_costs[2] = max_resource_costs[2].max(costs.obsidian);
-    }
-    let mut skip_bots = [false; 4];
-    for bot_index in 0..3 {
-        skip_bots[bot_index] = current_bots[bot_index] >= max_resource_costs[bot_index];
-    }
+        // First try buying things.
+        let bots_affordable = state.bots_affordable(blueprint);
+        let bot_costs = [
+            blueprint.ore_robot,
+            blueprint.clay_robot,
+            blueprint.obsidian_robot,
+            blueprint.geode_robot,
+        ];
+        let current_bots = [
+            state.ore_robots,
+            state.clay_robots,
+            state.obsidian_robots,
+            state.geode_robots,
+        ];
+        let mut max_resource_costs = [0; 3];
+        for costs in bot_costs {
+            max_resource_costs[0] = max_resource_costs[0].max(costs.ore);
+            max_resource_costs[1] = max_resource_costs[1].max(costs.clay);
+            max_resource_costs[2] = max_resource_costs[2].max(costs.obsidian);
+        }
+        let mut skip_bots = [false; 4];
+        for bot_index in 0..3 {
+            skip_bots[bot_index] = current_bots[bot_index] >= max_resource_costs[bot_index];
+        }
 
-    let bought_last_turn = last_buy.iter().any(|&b| b);
-    for bot_index in 0..4 {
-        if bots_affordable[bot_index] && !skip_bots[bot_index] {
-            if bot_index == 0 && minutes_left.saturating_sub(2) < bot_costs[0].ore {
-                continue;
-            }
-            if last_affordable[bot_index] && !bought_last_turn {
-                continue;
+        let bought_last_turn = last_buy.iter().any(|&b| b);
+        for bot_index in 0..4 {
+            if bots_affordable[bot_index] && !skip_bots[bot_index] {
+                if bot_index == 0 && minutes_left.saturating_sub(2) < bot_costs[0].ore {
+                    continue;
+                }
+                if last_affordable[bot_index] && !bought_last_turn {
+                    continue;
+                }
+                let mut new_bots = [false; 4];
+                new_bots[bot_index] = true;
+                next_states.push(Self {
+                    robots: state
+                        .spend(bot_costs[bot_index])
+                        .collect()
+                        .add_bots(new_bots),
+                    minutes_left: minutes_left - 1,
+                    last_buy: new_bots,
+                    last_affordable: bots_affordable,
+                });
             }
-            let mut new_bots = [false; 4];
-            new_bots[bot_index] = true;
-            let new_state = state
-                .spend(bot_costs[bot_index])
-                .collect()
-                .add_bots(new_bots);
-            max = max.max(search_recursive(
-                blueprint,
-                new_state,
-                minutes_left - 1,
-                max,
-                new_bots,
-                bots_affordable,
-            ));
         }
-    }
 
-    // Then try ending the turn without buying anything.
-    if bots_affordable.into_iter().any(|b| !b) {
-        max = max.max(search_recursive(
-            blueprint,
-            state.collect(),
-            minutes_left - 1,
-            max,
-            [false; 4],
-            bots_affordable,
-        ));
-    }
+        // Then try ending the turn without buying anything.
+        if bots_affordable.into_iter().any(|b| !b) {
+            next_states.push(Self {
+                robots: state.collect(),
+                minutes_left: minutes_left - 1,
+                last_buy: [false; 4],
+                last_affordable: bots_affordable,
+            });
+        }
 
-    max
+        next_states
+    }
 }
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
@@ -0,0 +1,156 @@
+use advent_of_code::parse::ints;
+
+advent_of_code::solution!(20);
+
+const DECRYPTION_KEY: i64 = 811_589_153;
+
+pub fn part_one(input: &str) -> Option<i64> {
+    let numbers = ints(input).collect::<Vec<_>>();
+    grove_coordinates(&mix(&numbers, 1))
+}
+
+pub fn part_two(input: &str) -> Option<i64> {
+    let numbers = ints(input)
+        .map(|number: i64| number * DECRYPTION_KEY)
+        .collect::<Vec<_>>();
+    grove_coordinates(&mix(&numbers, 10))
+}
+
+/// Returns the sum of the 1000th, 2000th and 3000th number after the 0.
+fn grove_coordinates(mixed: &[i64]) -> Option<i64> {
+    let zero = mixed.iter().position(|&number| number == 0)?;
+    Some(
+        [1000, 2000, 3000]
+            .into_iter()
+            .map(|offset| mixed[(zero + offset) % mixed.len()])
+            .sum(),
+    )
+}
+
+/// Moves every number forward or backward by its value, in their original order, `rounds` times.
+/// Returns the numbers in their mixed order, starting anywhere in the circle.
+fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    let mut list = MixingList::new(numbers.len());
+    // A number moving around the whole circle passes all other numbers and ends up where it started.
+    let others = (numbers.len() as i64 - 1).max(1);
+
+    for _ in 0..rounds {
+        for (index, &number) in numbers.iter().enumerate() {
+            let position = list.remove(index);
+            let target = (position as i64 + number).rem_euclid(others);
+            list.insert(target as usize, index);
+        }
+    }
+
+    list.indices().map(|index| numbers[index]).collect()
+}
+
+/// The order of the original indices of the numbers while they are mixed, split into blocks of about √n indices.
+///
+/// Finding, removing and inserting an index only touches the block sizes and a single block, so every move takes
+/// O(√n) instead of the O(n) of shifting a single `Vec`. The blocks are evened out again after √n moves.
+struct MixingList {
+    blocks: Vec<Vec<usize>>,
+    /// The block each original index is in.
+    block_of: Vec<usize>,
+    block_size: usize,
+    moves_since_rebalance: usize,
+}
+
+impl MixingList {
+    fn new(len: usize) -> Self {
+        let block_size = (len as f64).sqrt().ceil().max(1.0) as usize;
+        let mut list = Self {
+            blocks: vec![(0..len).collect()],
+            block_of: vec![0; len],
+            block_size,
+            moves_since_rebalance: 0,
+        };
+        list.rebalance();
+        list
+    }
+
+    fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().flatten().copied()
+    }
+
+    /// Splits the indices into blocks of equal size again.
+    fn rebalance(&mut self) {
+        let indices = self.indices().collect::<Vec<_>>();
+        self.blocks = indices
+            .chunks(self.block_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+        for (block, indices) in self.blocks.iter().enumerate() {
+            for &index in indices {
+                self.block_of[index] = block;
+            }
+        }
+        self.moves_since_rebalance = 0;
+    }
+
+    /// Removes the original `index` from the list and returns the position it was at.
+    fn remove(&mut self, index: usize) -> usize {
+        let block = self.block_of[index];
+        let offset = self.blocks[block]
+            .iter()
+            .position(|&i| i == index)
+            .expect("index should be in its block");
+        self.blocks[block].remove(offset);
+
+        self.blocks[..block].iter().map(Vec::len).sum::<usize>() + offset
+    }
+
+    /// Inserts the original `index` so that it ends up at `position`.
+    fn insert(&mut self, mut position: usize, index: usize) {
+        let mut block = 0;
+        while position > self.blocks[block].len() && block + 1 < self.blocks.len() {
+            position -= self.blocks[block].len();
+            block += 1;
+        }
+        self.blocks[block].insert(position, index);
+        self.block_of[index] = block;
+
+        self.moves_since_rebalance += 1;
+        if self.moves_since_rebalance >= self.block_size {
+            self.rebalance();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", PUZZLE));
+        assert_eq!(result, Some(3));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", PUZZLE));
+        assert_eq!(result, Some(1_623_178_306));
+    }
+
+    #[test]
+    fn test_mix_matches_naive_mixing() {
+        let numbers = (0..200_i64)
+            .map(|i| (i * 7919 % 601) - 300)
+            .collect::<Vec<_>>();
+
+        let mut naive = (0..numbers.len()).collect::<Vec<_>>();
+        for _ in 0..3 {
+            for (index, &number) in numbers.iter().enumerate() {
+                let position = naive.iter().position(|&i| i == index).unwrap();
+                naive.remove(position);
+                let target = (position as i64 + number).rem_euclid(naive.len() as i64);
+                naive.insert(target as usize, index);
+            }
+        }
+        let naive = naive.into_iter().map(|i| numbers[i]).collect::<Vec<_>>();
+
+        assert_eq!(mix(&numbers, 3), naive);
+    }
+}
@@ -0,0 +1,231 @@
+/// Exhaustive depth-first searches for optimisation puzzles: memoised recursion and branch and bound.
+///
+/// Both searches count the states they visit. With the `AOC_SEARCH_STATS` environment variable set, the counts are
+/// printed to stderr when a search finishes, which helps to tell whether a cache or bound is doing its job.
+use std::{collections::HashMap, env, fmt::Display, hash::Hash};
+
+/// Counters of a search, printed if `AOC_SEARCH_STATS` is set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// States that were expanded, i.e. computed or had their children generated.
+    pub states: usize,
+    /// States whose value was taken from the cache.
+    pub cache_hits: usize,
+    /// States that were skipped because their upper bound could not beat the best value so far.
+    pub prunes: usize,
+}
+
+impl SearchStats {
+    /// Prints the counters to stderr if the `AOC_SEARCH_STATS` environment variable is set, does nothing otherwise.
+    pub fn report(&self) {
+        if env::var_os("AOC_SEARCH_STATS").is_some() {
+            eprintln!("{self}");
+        }
+    }
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "search: {} states, {} cache hits, {} prunes",
+            self.states, self.cache_hits, self.prunes
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+type Step<'f, S, V> = &'f dyn Fn(&S, &mut Memo<'f, S, V>) -> V;
+
+/// A recursive function whose results are cached by state.
+///
+/// The step function computes the value of a state and calls [`Memo::get`] for the states it depends on.
+/// Each state is computed at most once.
+pub struct Memo<'f, S, V> {
+    step: Step<'f, S, V>,
+    cache: HashMap<S, V>,
+    stats: SearchStats,
+}
+
+impl<'f, S: Clone + Eq + Hash, V: Clone> Memo<'f, S, V> {
+    pub fn new(step: Step<'f, S, V>) -> Self {
+        Self {
+            step,
+            cache: HashMap::new(),
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Returns the value of `state`, computing it with the step function if it isn't cached yet.
+    pub fn get(&mut self, state: S) -> V {
+        if let Some(value) = self.cache.get(&state) {
+            self.stats.cache_hits += 1;
+            return value.clone();
+        }
+
+        self.stats.states += 1;
+        let step = self.step;
+        let value = step(&state, self);
+        self.cache.insert(state, value.clone());
+        value
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+}
+
+/// Computes the value of `start` with a memoised recursive `step` function, see [`Memo`].
+/// Prints the search statistics if `AOC_SEARCH_STATS` is set.
+pub fn memoized<S, V>(start: S, step: impl Fn(&S, &mut Memo<'_, S, V>) -> V) -> V
+where
+    S: Clone + Eq + Hash,
+    V: Clone,
+{
+    let mut memo = Memo::new(&step);
+    let value = memo.get(start);
+    memo.stats().report();
+    value
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Finds the highest value of any state reachable from `start` with a depth-first search.
+///
+/// - `children` returns the states following a state, which are explored in the order they are returned.
+/// - `value` is the value of a state as a solution. Every visited state is a candidate.
+/// - `upper_bound` is an optimistic estimate of the best value reachable from a state, including the state itself.
+///   States whose bound does not beat the best value found so far are pruned together with their descendants.
+///   A bound that is too low prunes the optimum, so when in doubt, overestimate.
+///
+/// Returns the best value and the search statistics, which are also printed if `AOC_SEARCH_STATS` is set.
+pub fn branch_and_bound<S, V, I>(
+    start: S,
+    mut children: impl FnMut(&S) -> I,
+    mut value: impl FnMut(&S) -> V,
+    mut upper_bound: impl FnMut(&S) -> V,
+) -> (V, SearchStats)
+where
+    V: Copy + Ord,
+    I: IntoIterator<Item = S>,
+{
+    let mut stats = SearchStats {
+        states: 1,
+        ..Default::default()
+    };
+    let mut best = value(&start);
+    let mut stack = vec![children(&start).into_iter()];
+
+    while let Some(siblings) = stack.last_mut() {
+        let Some(state) = siblings.next() else {
+            stack.pop();
+            continue;
+        };
+
+        if upper_bound(&state) <= best {
+            stats.prunes += 1;
+            continue;
+        }
+
+        stats.states += 1;
+        best = best.max(value(&state));
+        stack.push(children(&state).into_iter());
+    }
+
+    stats.report();
+    (best, stats)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{branch_and_bound, memoized, Memo, SearchStats};
+
+    #[test]
+    fn caches_states() {
+        // number of paths through a 10x10 grid, moving right or down.
+        let paths = |&(x, y): &(u8, u8), memo: &mut Memo<(u8, u8), u64>| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                memo.get((x - 1, y)) + memo.get((x, y - 1))
+            }
+        };
+        let mut memo = Memo::new(&paths);
+
+        assert_eq!(memo.get((10, 10)), 184_756);
+        assert_eq!(
+            memo.stats(),
+            SearchStats {
+                states: 120,
+                cache_hits: 81,
+                prunes: 0
+            }
+        );
+        assert_eq!(memoized((10, 10), paths), 184_756);
+    }
+
+    #[test]
+    fn computes_each_state_once() {
+        let fibonacci = |&n: &u64, memo: &mut Memo<u64, u64>| {
+            if n < 2 {
+                n
+            } else {
+                memo.get(n - 1) + memo.get(n - 2)
+            }
+        };
+        let mut memo = Memo::new(&fibonacci);
+
+        assert_eq!(memo.get(90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.stats().states, 91);
+    }
+
+    #[test]
+    fn prunes_with_upper_bound() {
+        // highest sum of a root to leaf path in a complete binary tree of depth 6, node n has value n % 7.
+        let children = |&(n, sum): &(u32, u32)| {
+            let leaf = n >= 64;
+            [2 * n, 2 * n + 1]
+                .into_iter()
+                .filter(move |_| !leaf)
+                .map(move |child| (child, sum + child % 7))
+        };
+        let value = |&(_, sum): &(u32, u32)| sum;
+        let levels_left = |n: u32| 6 - (31 - n.leading_zeros());
+
+        let (unbounded, full) = branch_and_bound((1, 1), children, value, |_| u32::MAX);
+        let (bounded, pruned) = branch_and_bound((1, 1), children, value, |&(n, sum)| {
+            sum + 6 * levels_left(n)
+        });
+
+        assert_eq!(unbounded, 34);
+        assert_eq!(bounded, unbounded);
+        assert_eq!(full.states, 127);
+        assert_eq!(full.prunes, 0);
+        assert!(pruned.prunes > 0);
+        assert!(pruned.states < full.states);
+    }
+
+    #[test]
+    fn solves_knapsack() {
+        // pick a subset of the items with the highest total value that fits a capacity of 10.
+        let items = [(5, 10), (4, 40), (6, 30), (3, 50)];
+        let (best, _) = branch_and_bound(
+            (0, 0, 0),
+            |&(i, weight, value): &(usize, u32, u32)| match items.get(i) {
+                None => vec![],
+                Some(&(w, v)) if weight + w <= 10 => {
+                    vec![(i + 1, weight + w, value + v), (i + 1, weight, value)]
+                }
+                Some(_) => vec![(i + 1, weight, value)],
+            },
+            |&(_, _, value)| value,
+            |&(i, _, value)| value + items[i..].iter().map(|(_, v)| v).sum::<u32>(),
+        );
+
+        assert_eq!(best, 90);
+    }
+}
@@ -0,0 +1,586 @@
+/// Points, directions and distances on integer coordinates.
+///
+/// Coordinates use screen orientation: `x` grows to the right and `y` grows downwards, matching the rows of a [`Grid`](crate::grid::Grid).
+use std::{
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// Signed integer types that can be used as coordinates.
+pub trait Coord:
+    Copy
+    + Ord
+    + Default
+    + Hash
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + From<i8>
+{
+}
+
+impl<T> Coord for T where
+    T: Copy
+        + Ord
+        + Default
+        + Hash
+        + Debug
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Neg<Output = T>
+        + From<i8>
+{
+}
+
+fn abs_diff<T: Coord>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn signum<T: Coord>(value: T) -> T {
+    T::from(match value.cmp(&T::default()) {
+        std::cmp::Ordering::Less => -1,
+        std::cmp::Ordering::Equal => 0,
+        std::cmp::Ordering::Greater => 1,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The 4 orthogonal directions, in clockwise order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// Returns the direction after a quarter turn clockwise.
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Returns the direction after a quarter turn counter-clockwise.
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Returns the unit vector of the direction.
+    #[must_use]
+    pub fn offset<T: Coord>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::Up => (0, -1),
+            Self::Right => (1, 0),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+/// The 8 compass directions including diagonals, in clockwise order starting north (up).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Self; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    /// Returns the direction after an eighth turn clockwise.
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Returns the direction after an eighth turn counter-clockwise.
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Returns the vector of the direction, diagonals have a length of one in both axes.
+    #[must_use]
+    pub fn offset<T: Coord>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::N => (0, -1),
+            Self::NE => (1, -1),
+            Self::E => (1, 0),
+            Self::SE => (1, 1),
+            Self::S => (0, 1),
+            Self::SW => (-1, 1),
+            Self::W => (-1, 0),
+            Self::NW => (-1, -1),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(value: Dir4) -> Self {
+        match value {
+            Dir4::Up => Self::N,
+            Dir4::Right => Self::E,
+            Dir4::Down => Self::S,
+            Dir4::Left => Self::W,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A point or vector in the plane.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    #[must_use]
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Coord> Point2<T> {
+    #[must_use]
+    pub fn origin() -> Self {
+        Self::default()
+    }
+
+    /// Sum of the distances along both axes, i.e. the number of orthogonal steps between the points.
+    #[must_use]
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Largest distance along either axis, i.e. the number of steps between the points when moving diagonally is allowed.
+    #[must_use]
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// Returns the vector with every component replaced by its sign.
+    #[must_use]
+    pub fn signum(self) -> Self {
+        Self::new(signum(self.x), signum(self.y))
+    }
+
+    /// Rotates the vector by a quarter turn clockwise around the origin.
+    #[must_use]
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates the vector by a quarter turn counter-clockwise around the origin.
+    #[must_use]
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Returns the orthogonal neighbours, in the order of [`Dir4::ALL`].
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Dir4::ALL.into_iter().map(move |dir| self + dir)
+    }
+
+    /// Returns the neighbours including diagonal ones, in the order of [`Dir8::ALL`].
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        Dir8::ALL.into_iter().map(move |dir| self + dir)
+    }
+
+    /// Returns every point on the line from `self` to `other`, including both ends.
+    ///
+    /// Horizontal, vertical and diagonal lines visit every point they pass through, other lines are rasterised with Bresenham's algorithm.
+    pub fn line_to(self, other: Self) -> Line<T> {
+        Line::new(self, other)
+    }
+}
+
+impl<T: Debug> Debug for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:?}, {:?})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T: Coord> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coord> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coord> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Coord> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Coord> Add<Dir4> for Point2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir4) -> Self {
+        self + rhs.offset()
+    }
+}
+
+impl<T: Coord> Add<Dir8> for Point2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir8) -> Self {
+        self + rhs.offset()
+    }
+}
+
+impl<T: Coord, R> AddAssign<R> for Point2<T>
+where
+    Self: Add<R, Output = Self>,
+{
+    fn add_assign(&mut self, rhs: R) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coord> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Iterator over the points of a line, see [`Point2::line_to`].
+#[derive(Debug, Clone)]
+pub struct Line<T> {
+    current: Point2<T>,
+    end: Point2<T>,
+    step: Point2<T>,
+    dx: T,
+    dy: T,
+    error: T,
+    done: bool,
+}
+
+impl<T: Coord> Line<T> {
+    fn new(start: Point2<T>, end: Point2<T>) -> Self {
+        let dx = abs_diff(start.x, end.x);
+        let dy = -abs_diff(start.y, end.y);
+        Self {
+            current: start,
+            end,
+            step: (end - start).signum(),
+            dx,
+            dy,
+            error: dx + dy,
+            done: false,
+        }
+    }
+}
+
+impl<T: Coord> Iterator for Line<T> {
+    type Item = Point2<T>;
+
+    fn next(&mut self) -> Option<Point2<T>> {
+        if self.done {
+            return None;
+        }
+
+        let point = self.current;
+        if point == self.end {
+            self.done = true;
+            return Some(point);
+        }
+
+        let double_error = self.error + self.error;
+        if double_error >= self.dy {
+            self.error = self.error + self.dy;
+            self.current.x = self.current.x + self.step.x;
+        }
+        if double_error <= self.dx {
+            self.error = self.error + self.dx;
+            self.current.y = self.current.y + self.step.y;
+        }
+
+        Some(point)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A point or vector in space.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    #[must_use]
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    #[must_use]
+    pub fn origin() -> Self {
+        Self::default()
+    }
+
+    /// Sum of the distances along all axes.
+    #[must_use]
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    /// Largest distance along any axis.
+    #[must_use]
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x)
+            .max(abs_diff(self.y, other.y))
+            .max(abs_diff(self.z, other.z))
+    }
+
+    #[must_use]
+    pub fn signum(self) -> Self {
+        Self::new(signum(self.x), signum(self.y), signum(self.z))
+    }
+
+    /// Rotates the vector by a quarter turn around the x axis, following the right-hand rule.
+    #[must_use]
+    pub fn rotate_x(self) -> Self {
+        Self::new(self.x, -self.z, self.y)
+    }
+
+    /// Rotates the vector by a quarter turn around the y axis, following the right-hand rule.
+    #[must_use]
+    pub fn rotate_y(self) -> Self {
+        Self::new(self.z, self.y, -self.x)
+    }
+
+    /// Rotates the vector by a quarter turn around the z axis, following the right-hand rule.
+    #[must_use]
+    pub fn rotate_z(self) -> Self {
+        Self::new(-self.y, self.x, self.z)
+    }
+
+    /// Returns the 6 neighbours that share a face with the unit cube at this point.
+    pub fn neighbors6(self) -> impl Iterator<Item = Self> {
+        let (one, zero) = (T::from(1), T::from(0));
+        [
+            Self::new(-one, zero, zero),
+            Self::new(one, zero, zero),
+            Self::new(zero, -one, zero),
+            Self::new(zero, one, zero),
+            Self::new(zero, zero, -one),
+            Self::new(zero, zero, one),
+        ]
+        .into_iter()
+        .map(move |offset| self + offset)
+    }
+}
+
+impl<T: Debug> Debug for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:?}, {:?}, {:?})", self.x, self.y, self.z)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: Coord> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Coord> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Coord> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Coord> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Coord> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coord> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Dir4, Dir8, Point2, Point3};
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Left.opposite(), Dir4::Right);
+        assert_eq!(Dir8::NW.turn_right(), Dir8::N);
+        assert_eq!(Dir8::N.turn_left(), Dir8::NW);
+        assert_eq!(Dir8::from(Dir4::Down).opposite(), Dir8::N);
+    }
+
+    #[test]
+    fn rotations_match_turns() {
+        for dir in Dir4::ALL {
+            let offset = dir.offset::<i32>();
+            assert_eq!(offset.rotate_right(), dir.turn_right().offset());
+            assert_eq!(offset.rotate_left(), dir.turn_left().offset());
+        }
+
+        let p = Point3::new(1, 2, 3);
+        assert_eq!(p.rotate_z(), Point3::new(-2, 1, 3));
+        assert_eq!(p.rotate_x().rotate_x().rotate_x().rotate_x(), p);
+        assert_eq!(p.rotate_y().rotate_y(), Point3::new(-1, 2, -3));
+    }
+
+    #[test]
+    fn computes_distances() {
+        let (a, b) = (Point2::new(1, -2), Point2::new(-3, 4));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!((b - a).signum(), Point2::new(-1, 1));
+
+        let (a, b) = (Point3::new(1_i64, 2, 3), Point3::new(2, 0, 3));
+        assert_eq!(a.manhattan(b), 3);
+        assert_eq!(a.chebyshev(b), 2);
+        assert_eq!(a.neighbors6().filter(|n| n.manhattan(a) == 1).count(), 6);
+    }
+
+    #[test]
+    fn moves_points() {
+        let mut p = Point2::new(0_i16, 0);
+        p += Dir4::Down;
+        p += Dir8::NE;
+        p += Point2::new(2, 2) * 2;
+        assert_eq!(p, Point2::new(5, 4));
+        assert_eq!(p.neighbors8().filter(|n| n.chebyshev(p) == 1).count(), 8);
+    }
+
+    #[test]
+    fn rasterises_lines() {
+        let line = |a: (i32, i32), b: (i32, i32)| {
+            Point2::from(a)
+                .line_to(b.into())
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(line((2, 1), (2, 1)), vec![(2, 1)]);
+        assert_eq!(line((3, 4), (3, 2)), vec![(3, 4), (3, 3), (3, 2)]);
+        assert_eq!(line((0, 0), (-2, 0)), vec![(0, 0), (-1, 0), (-2, 0)]);
+        assert_eq!(line((0, 0), (2, 2)), vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(line((0, 0), (2, -2)), vec![(0, 0), (1, -1), (2, -2)]);
+        assert_eq!(line((0, 0), (4, 2)).len(), 5);
+        assert_eq!(line((0, 0), (4, 2)).last(), Some(&(4, 2)));
+    }
+}
@@ -0,0 +1,545 @@
+/// Two-dimensional grids, as used by many puzzles with character maps as input.
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Write},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::geom::Point2;
+
+/// A position in a dense [`Grid`], counted from the top left corner.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    #[must_use]
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Returns the position moved by `d_row` rows and `d_col` columns, or `None` if it would move above or left of the grid.
+    #[must_use]
+    pub fn offset(self, d_row: isize, d_col: isize) -> Option<Self> {
+        Some(Self {
+            row: self.row.checked_add_signed(d_row)?,
+            col: self.col.checked_add_signed(d_col)?,
+        })
+    }
+}
+
+/// Offsets of the 4 orthogonal neighbours: up, left, right and down.
+const NEIGHBORS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of the 8 neighbours including diagonals, in reading order.
+const NEIGHBORS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// The input has no lines.
+    Empty,
+    /// A line is shorter or longer than the first line. `row` starts at 0.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::Empty => f.write_str("grid is empty"),
+            ParseGridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {found} columns, expected {expected}",
+                row + 1
+            ),
+        }
+    }
+}
+
+impl Error for ParseGridError {}
+
+/* -------------------------------------------------------------------------- */
+
+/// A dense, rectangular grid, stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of `width` by `height` cells, all set to `value`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Creates a grid from cells in row order.
+    ///
+    /// # Panics
+    /// Panics if the number of cells is not a multiple of `width`.
+    #[must_use]
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells don't fit into rows of {width}",
+            cells.len()
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parses a character map, calling `f` with the position and character of every cell.
+    /// Trailing empty lines and carriage returns are ignored.
+    pub fn parse(input: &str, mut f: impl FnMut(Pos, char) -> T) -> Result<Self, ParseGridError> {
+        let mut width = None;
+        let mut cells = vec![];
+
+        let lines = input.trim_end_matches(['\n', '\r']).lines();
+        for (row, line) in lines.enumerate() {
+            let line = line.trim_end_matches('\r');
+            let start = cells.len();
+            cells.extend(
+                line.chars()
+                    .enumerate()
+                    .map(|(col, ch)| f(Pos::new(row, col), ch)),
+            );
+
+            let found = cells.len() - start;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(ParseGridError::RaggedRow {
+                        row,
+                        expected,
+                        found,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        match width {
+            None | Some(0) => Err(ParseGridError::Empty),
+            Some(width) => Ok(Self::from_vec(width, cells)),
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if `pos` lies within the grid.
+    #[must_use]
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.row * self.width + pos.col])
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.row * self.width + pos.col])
+    }
+
+    /// Returns the cell at a position given as signed row and column, `None` if it lies outside the grid.
+    #[must_use]
+    pub fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        self.get(Pos::new(row.try_into().ok()?, col.try_into().ok()?))
+    }
+
+    /// Returns the cells of a row, from left to right.
+    ///
+    /// # Panics
+    /// Panics if `row` is out of bounds.
+    #[must_use]
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.height, "row {row} out of bounds");
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        self.cells.chunks_exact(self.width)
+    }
+
+    /// Returns the cells of a column, from top to bottom.
+    ///
+    /// # Panics
+    /// Panics if `col` is out of bounds.
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(col < self.width, "column {col} out of bounds");
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    /// Returns all positions of the grid in row order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Pos::new(i / width, i % width))
+    }
+
+    /// Returns all cells with their positions in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Returns the cells in row order.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Returns the position of the first cell in row order that matches `predicate`.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        let i = self.cells.iter().position(predicate)?;
+        Some(Pos::new(i / self.width, i % self.width))
+    }
+
+    /// Returns the in-bounds orthogonal neighbours of `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, &NEIGHBORS_4)
+    }
+
+    /// Returns the in-bounds neighbours of `pos`, including diagonal ones.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, &NEIGHBORS_8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        pos: Pos,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&(d_row, d_col)| pos.offset(d_row, d_col))
+            .filter(|&n| self.contains(n))
+    }
+
+    /// Creates a grid of the same size by applying `f` to every cell.
+    #[must_use]
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "{pos:?} out of bounds for grid of {}x{}",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} out of bounds for grid of {width}x{height}"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, |_, ch| ch)
+    }
+}
+
+/// Renders every row as one line of cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A grid of unbounded size that only stores occupied cells, keyed by [`Point2`] with `y` growing downwards.
+/// Keeps track of the bounding box of all cells ever inserted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2<i64>, T>,
+    min: Point2<i64>,
+    max: Point2<i64>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            min: Point2::new(i64::MAX, i64::MAX),
+            max: Point2::new(i64::MIN, i64::MIN),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a character map with the top left character at the origin.
+    /// Cells for which `f` returns `None` are left empty.
+    pub fn parse(input: &str, mut f: impl FnMut(Point2<i64>, char) -> Option<T>) -> Self {
+        let mut grid = Self::new();
+        for (y, line) in (0..).zip(input.lines()) {
+            for (x, ch) in (0..).zip(line.trim_end_matches('\r').chars()) {
+                let pos = Point2::new(x, y);
+                if let Some(value) = f(pos, ch) {
+                    grid.insert(pos, value);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Sets the cell at `pos`, returning its previous value.
+    pub fn insert(&mut self, pos: Point2<i64>, value: T) -> Option<T> {
+        self.min = Point2::new(self.min.x.min(pos.x), self.min.y.min(pos.y));
+        self.max = Point2::new(self.max.x.max(pos.x), self.max.y.max(pos.y));
+        self.cells.insert(pos, value)
+    }
+
+    /// Clears the cell at `pos`. The bounding box is not shrunk.
+    pub fn remove(&mut self, pos: Point2<i64>) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Point2<i64>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, pos: Point2<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    #[must_use]
+    pub fn contains(&self, pos: Point2<i64>) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Returns the number of occupied cells.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the smallest and largest coordinates of all cells ever inserted, `None` for an empty grid.
+    #[must_use]
+    pub fn bounds(&self) -> Option<(Point2<i64>, Point2<i64>)> {
+        (self.min.x <= self.max.x).then_some((self.min, self.max))
+    }
+
+    /// Returns all occupied cells in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells.iter().map(|(&pos, value)| (pos, value))
+    }
+
+    /// Returns the occupied orthogonal neighbours of `pos`.
+    pub fn neighbors4(&self, pos: Point2<i64>) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        pos.neighbors4()
+            .filter_map(|n| self.get(n).map(|value| (n, value)))
+    }
+
+    /// Returns the occupied neighbours of `pos`, including diagonal ones.
+    pub fn neighbors8(&self, pos: Point2<i64>) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        pos.neighbors8()
+            .filter_map(|n| self.get(n).map(|value| (n, value)))
+    }
+
+    /// Copies the bounding box into a dense [`Grid`], filling empty cells with `empty`.
+    /// Returns the grid and the point of its top left cell.
+    #[must_use]
+    pub fn to_dense(&self, empty: T) -> Option<(Grid<T>, Point2<i64>)>
+    where
+        T: Clone,
+    {
+        let (min, max) = self.bounds()?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+
+        let mut grid = Grid::new(width, height, empty);
+        for (&point, value) in &self.cells {
+            let offset = point - min;
+            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+            let pos = Pos::new(offset.y as usize, offset.x as usize);
+            grid[pos] = value.clone();
+        }
+        Some((grid, min))
+    }
+}
+
+/// Renders the bounding box, with `.` for empty cells.
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.get(Point2::new(x, y)) {
+                    Some(value) => write!(f, "{value}")?,
+                    None => f.write_char('.')?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, ParseGridError, Pos, SparseGrid};
+    use crate::geom::Point2;
+
+    fn grid() -> Grid<u32> {
+        Grid::parse("123\r\n456\r\n", |_, ch| ch.to_digit(10).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_character_maps() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 2)], 6);
+        assert_eq!(grid.get(Pos::new(0, 3)), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(1, 0), Some(&4));
+    }
+
+    #[test]
+    fn parses_characters_with_from_str() {
+        let grid: Grid<char> = "ab\ncd".parse().unwrap();
+        assert_eq!(grid[Pos::new(1, 0)], 'c');
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.neighbors4(Pos::new(0, 0)).count(), 2);
+        assert_eq!(grid.to_string(), "ab\ncd\n");
+    }
+
+    #[test]
+    fn rejects_ragged_and_empty_maps() {
+        assert_eq!(
+            "ab\nc".parse::<Grid<char>>(),
+            Err(ParseGridError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!("\n".parse::<Grid<char>>(), Err(ParseGridError::Empty));
+    }
+
+    #[test]
+    fn views_rows_and_columns() {
+        let grid = grid();
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(
+            grid.column(2).rev().copied().collect::<Vec<_>>(),
+            vec![6, 3]
+        );
+        assert_eq!(grid.position(|&v| v == 5), Some(Pos::new(1, 1)));
+    }
+
+    #[test]
+    fn iterates_neighbors() {
+        let grid = grid();
+        let n4: Vec<_> = grid.neighbors4(Pos::new(0, 1)).map(|p| grid[p]).collect();
+        assert_eq!(n4, vec![1, 3, 5]);
+        let n8: Vec<_> = grid.neighbors8(Pos::new(0, 1)).map(|p| grid[p]).collect();
+        assert_eq!(n8, vec![1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_on_out_of_bounds_index() {
+        let _ = grid()[Pos::new(2, 0)];
+    }
+
+    #[test]
+    fn renders_grids() {
+        assert_eq!(grid().map(|v| v * 2 % 10).to_string(), "246\n802\n");
+    }
+
+    #[test]
+    fn tracks_sparse_cells() {
+        let mut grid = SparseGrid::parse("#.\n.#", |_, ch| (ch == '#').then_some('#'));
+        grid.insert(Point2::new(-1, 0), 'o');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.bounds(), Some((Point2::new(-1, 0), Point2::new(1, 1))));
+        assert_eq!(grid.neighbors8(Point2::new(0, 0)).count(), 2);
+        assert_eq!(grid.to_string(), "o#.\n..#\n");
+
+        let (dense, origin) = grid.to_dense('.').unwrap();
+        assert_eq!(origin, Point2::new(-1, 0));
+        assert_eq!(dense.to_string(), "o#.\n..#\n");
+    }
+}
@@ -0,0 +1,284 @@
+/// Sets of integers stored as sorted, disjoint ranges.
+use std::{
+    cmp::{max, min},
+    ops::{Add, Range, Sub},
+};
+
+/// A set of values stored as sorted, non-overlapping half-open ranges.
+/// Overlapping and adjacent ranges are merged on insertion, so every value is covered by at most one range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the ranges of the set, sorted and without overlaps.
+    #[must_use]
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// Returns `true` if the set contains no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Index of the first range that ends after `value`, i.e. the only range that might contain it.
+    fn first_ending_after(&self, value: T) -> usize {
+        self.ranges.partition_point(|r| r.end <= value)
+    }
+
+    /// Adds all values of `range` to the set, merging it with overlapping and adjacent ranges.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let lo = self.ranges.partition_point(|r| r.end < range.start);
+        let hi = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let merged = if lo < hi {
+            min(range.start, self.ranges[lo].start)..max(range.end, self.ranges[hi - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(lo..hi, [merged]);
+    }
+
+    /// Removes all values of `range` from the set, splitting ranges that only partly overlap it.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let lo = self.first_ending_after(range.start);
+        let hi = self.ranges.partition_point(|r| r.start < range.end);
+        if lo >= hi {
+            return;
+        }
+
+        let (first, last) = (self.ranges[lo].clone(), self.ranges[hi - 1].clone());
+        let remaining = [first.start..range.start, range.end..last.end];
+        self.ranges
+            .splice(lo..hi, remaining.into_iter().filter(|r| !r.is_empty()));
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .get(self.first_ending_after(value))
+            .is_some_and(|r| r.start <= value)
+    }
+
+    /// Returns `true` if every value of `range` is in the set.
+    #[must_use]
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        range.is_empty()
+            || self
+                .ranges
+                .get(self.first_ending_after(range.start))
+                .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Returns `true` if any value of `range` is in the set.
+    #[must_use]
+    pub fn overlaps(&self, range: &Range<T>) -> bool {
+        !range.is_empty()
+            && self
+                .ranges
+                .get(self.first_ending_after(range.start))
+                .is_some_and(|r| r.start < range.end)
+    }
+
+    /// Returns the values that are in either set.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.extend(other.ranges.iter().cloned());
+        result
+    }
+
+    /// Returns the values that are in both sets.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let overlap = max(x.start, y.start)..min(x.end, y.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // advance the range that ends first, it can't overlap anything else of the other set.
+            if x.end <= y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Returns the values of this set that are not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.remove(range.clone());
+        }
+        result
+    }
+
+    /// Returns the ranges within `bounds` that are not in the set, in ascending order.
+    pub fn gaps(&self, bounds: Range<T>) -> impl Iterator<Item = Range<T>> + '_ {
+        let lo = self.first_ending_after(bounds.start);
+        let mut cursor = bounds.start;
+
+        self.ranges[lo..]
+            .iter()
+            .take_while(move |r| r.start < bounds.end)
+            .map(|r| r.start..r.end)
+            .chain([bounds.end..bounds.end])
+            .filter_map(move |r| {
+                let gap = cursor..min(r.start, bounds.end);
+                cursor = max(cursor, r.end);
+                (!gap.is_empty()).then_some(gap)
+            })
+    }
+
+    /// Returns the number of values in the set, i.e. the summed length of all ranges.
+    #[must_use]
+    pub fn total_len(&self) -> T
+    where
+        T: Default + Add<Output = T> + Sub<Output = T>,
+    {
+        self.ranges
+            .iter()
+            .fold(T::default(), |len, r| len + (r.end - r.start))
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Copy + Ord> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::IntervalSet;
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(start, end)| start..end).collect()
+    }
+
+    #[test]
+    fn merges_overlapping_and_adjacent_ranges() {
+        let mut s = set(&[(10, 12), (0, 2), (4, 6)]);
+        assert_eq!(s.ranges(), &[0..2, 4..6, 10..12]);
+
+        s.insert(2..4);
+        assert_eq!(s.ranges(), &[0..6, 10..12]);
+        s.insert(5..11);
+        assert_eq!(s.ranges(), &[0..12]);
+        s.insert(3..3);
+        assert_eq!(s.ranges(), &[0..12]);
+        s.insert(-5..-3);
+        assert_eq!(s.ranges(), &[-5..-3, 0..12]);
+    }
+
+    #[test]
+    fn collects_ranges() {
+        let mut s: IntervalSet<i32> = [0..5, 3..8, 10..12].into_iter().collect();
+        assert_eq!(s.ranges(), &[0..8, 10..12]);
+
+        s.remove(2..4);
+        assert_eq!(s.total_len(), 8);
+        assert_eq!(s.gaps(0..15).collect::<Vec<_>>(), vec![2..4, 8..10, 12..15]);
+    }
+
+    #[test]
+    fn removes_ranges() {
+        let mut s = set(&[(0, 10), (20, 30)]);
+        s.remove(5..25);
+        assert_eq!(s.ranges(), &[0..5, 25..30]);
+        s.remove(1..2);
+        assert_eq!(s.ranges(), &[0..1, 2..5, 25..30]);
+        s.remove(-10..100);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn checks_membership() {
+        let s = set(&[(0, 5), (10, 15)]);
+        assert!(s.contains(0));
+        assert!(!s.contains(5));
+        assert!(s.contains(14));
+        assert!(s.contains_range(&(1..5)));
+        assert!(!s.contains_range(&(4..11)));
+        assert!(s.overlaps(&(4..11)));
+        assert!(!s.overlaps(&(5..10)));
+    }
+
+    #[test]
+    fn combines_sets() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b).ranges(), &[0..40]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..40]);
+    }
+
+    #[test]
+    fn finds_gaps_and_length() {
+        let s = set(&[(0, 5), (10, 15), (20, 25)]);
+        assert_eq!(s.total_len(), 15);
+        assert_eq!(s.gaps(3..22).collect::<Vec<_>>(), vec![5..10, 15..20]);
+        assert_eq!(
+            s.gaps(-2..30).collect::<Vec<_>>(),
+            vec![-2..0, 5..10, 15..20, 25..30]
+        );
+        assert_eq!(s.gaps(11..14).count(), 0);
+        assert_eq!(
+            IntervalSet::new().gaps(1..3).collect::<Vec<_>>(),
+            vec![1..3]
+        );
+    }
+}
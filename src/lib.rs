@@ -1,3 +1,9 @@
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
+pub mod dfs;
+pub mod geom;
+pub mod grid;
+pub mod interval;
+pub mod parse;
+pub mod search;
@@ -1,77 +1,155 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, download, read, scaffold, solve, time, verify};
+use advent_of_code::template::registry::Registry;
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::Puzzle;
 #[cfg(feature = "today")]
 use std::process;
 
+/// Solutions compiled into this binary with the `registry` feature, generated by `build.rs`.
+mod solutions {
+    include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+}
+
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::commands::{all, time};
+    use advent_of_code::template::{OutputFormat, Puzzle, DEFAULT_YEAR};
     use std::process;
+    use std::time::Duration;
 
     pub enum AppArguments {
         Download {
-            day: Day,
+            puzzle: Puzzle,
         },
         Read {
-            day: Day,
+            puzzle: Puzzle,
         },
         Scaffold {
-            day: Day,
+            puzzle: Puzzle,
             download: bool,
         },
         Solve {
-            day: Day,
+            puzzle: Puzzle,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            format: OutputFormat,
         },
         All {
+            year: u16,
             release: bool,
+            format: OutputFormat,
+            jobs: usize,
+            timeout: Option<Duration>,
         },
         Time {
             all: bool,
-            day: Option<Day>,
+            year: u16,
+            puzzle: Option<Puzzle>,
+            store: bool,
+            compare: Option<f64>,
+            dhat: bool,
+            format: OutputFormat,
+        },
+        Verify {
+            year: u16,
+            puzzle: Option<Puzzle>,
+            release: bool,
             store: bool,
+            timeout: Option<Duration>,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
+    /// Parses `--timeout <seconds>`, where `0` disables the timeout.
+    fn parse_timeout(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<Duration>, pico_args::Error> {
+        let seconds = args.opt_value_from_str::<_, u64>("--timeout")?;
+        Ok(match seconds {
+            None => Some(all::DEFAULT_TIMEOUT),
+            Some(0) => None,
+            Some(seconds) => Some(Duration::from_secs(seconds)),
+        })
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
+        let subcommand = args.subcommand()?;
+        // NOTE: options have to be parsed before free-standing arguments.
+        let year: u16 = args.opt_value_from_str("--year")?.unwrap_or(DEFAULT_YEAR);
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let app_args = match subcommand.as_deref() {
             Some("all") => AppArguments::All {
+                year,
                 release: args.contains("--release"),
+                format: args.opt_value_from_str("--format")?.unwrap_or_default(),
+                jobs: args.opt_value_from_str("--jobs")?.unwrap_or(1),
+                timeout: parse_timeout(&mut args)?,
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let compare = args.contains("--compare");
+                let dhat = args.contains("--dhat");
+                let threshold = args
+                    .opt_value_from_str("--threshold")?
+                    .unwrap_or(time::DEFAULT_REGRESSION_THRESHOLD);
+                let format = args.opt_value_from_str("--format")?.unwrap_or_default();
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
+                    year,
+                    puzzle: args.opt_free_from_str()?.map(|day| Puzzle::new(year, day)),
                     store,
+                    compare: compare.then_some(threshold),
+                    dhat,
+                    format,
                 }
             }
             Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+                puzzle: Puzzle::new(year, args.free_from_str()?),
             },
             Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
-            },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+                puzzle: Puzzle::new(year, args.free_from_str()?),
             },
+            Some("scaffold") => {
+                let download = args.contains("--download");
+
+                AppArguments::Scaffold {
+                    puzzle: Puzzle::new(year, args.free_from_str()?),
+                    download,
+                }
+            }
+            Some("solve") => {
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let format = args.opt_value_from_str("--format")?.unwrap_or_default();
+
+                AppArguments::Solve {
+                    puzzle: Puzzle::new(year, args.free_from_str()?),
+                    release,
+                    submit,
+                    dhat,
+                    format,
+                }
+            }
+            Some("verify") => {
+                let release = args.contains("--release");
+                let store = args.contains("--store");
+                let timeout = parse_timeout(&mut args)?;
+
+                AppArguments::Verify {
+                    year,
+                    puzzle: args.opt_free_from_str()?.map(|day| Puzzle::new(year, day)),
+                    release,
+                    store,
+                    timeout,
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -93,6 +171,12 @@ mod args {
     }
 }
 
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    solutions::register_all(&mut registry);
+    registry
+}
+
 fn main() {
     match parse() {
         Err(err) => {
@@ -100,29 +184,57 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day, download } => {
-                scaffold::handle(day);
+            AppArguments::All {
+                year,
+                release,
+                format,
+                jobs,
+                timeout,
+            } => all::handle(&registry(), year, release, format, jobs, timeout),
+            AppArguments::Time {
+                year,
+                puzzle,
+                all,
+                store,
+                compare,
+                dhat,
+                format,
+            } => {
+                if dhat {
+                    time::handle_dhat(&registry(), year, puzzle, store, format);
+                } else {
+                    time::handle(&registry(), year, puzzle, all, store, compare, format);
+                }
+            }
+            AppArguments::Download { puzzle } => download::handle(puzzle),
+            AppArguments::Read { puzzle } => read::handle(puzzle),
+            AppArguments::Scaffold { puzzle, download } => {
+                scaffold::handle(puzzle);
                 if download {
-                    download::handle(day);
+                    download::handle(puzzle);
                 }
             }
             AppArguments::Solve {
-                day,
+                puzzle,
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                format,
+            } => solve::handle(puzzle, release, dhat, submit, format),
+            AppArguments::Verify {
+                year,
+                puzzle,
+                release,
+                store,
+                timeout,
+            } => verify::handle(&registry(), year, puzzle, release, store, timeout),
             #[cfg(feature = "today")]
             AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day);
-                        download::handle(day);
-                        read::handle(day)
+                match Puzzle::today() {
+                    Some(puzzle) => {
+                        scaffold::handle(puzzle);
+                        download::handle(puzzle);
+                        read::handle(puzzle)
                     }
                     None => {
                         eprintln!(
@@ -0,0 +1,292 @@
+/// Allocation-free helpers to pick numbers and fields out of puzzle inputs.
+///
+/// All helpers borrow from the input and tolerate Windows line endings, so days don't have to `trim` every line.
+use std::{iter::FusedIterator, marker::PhantomData};
+
+/// Primitive integers that can be parsed by the helpers of this module.
+pub trait Integer: Copy {
+    /// Appends a decimal digit, subtracting it instead for negative numbers. Returns `None` on overflow.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+    fn zero() -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                #[inline]
+                fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                    let shifted = self.checked_mul(10)?;
+                    if negative {
+                        shifted.checked_sub(digit as $t)
+                    } else {
+                        shifted.checked_add(digit as $t)
+                    }
+                }
+
+                #[inline]
+                fn zero() -> Self {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+fn digits<T: Integer>(bytes: &[u8], negative: bool) -> Option<T> {
+    if bytes.is_empty() {
+        return None;
+    }
+    bytes.iter().try_fold(T::zero(), |n, &b| {
+        b.is_ascii_digit()
+            .then(|| n.push_digit(b - b'0', negative))
+            .flatten()
+    })
+}
+
+/// Parses a string of decimal digits without a sign.
+/// Returns `None` if it's empty, contains anything but digits or overflows `T`.
+pub fn uint<T: Integer>(s: impl AsRef<[u8]>) -> Option<T> {
+    digits(s.as_ref(), false)
+}
+
+/// Parses a decimal integer with an optional leading `+` or `-`.
+/// Returns `None` if it's not a number or overflows `T`, which includes negative numbers for unsigned `T`.
+pub fn int<T: Integer>(s: impl AsRef<[u8]>) -> Option<T> {
+    match s.as_ref() {
+        [b'-', rest @ ..] => digits(rest, true),
+        [b'+', rest @ ..] => digits(rest, false),
+        bytes => digits(bytes, false),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Iterator over the integers in a string, see [`ints`] and [`uints`].
+#[derive(Debug, Clone)]
+pub struct Ints<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    signed: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Integer> Iterator for Ints<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let start = self.pos + self.bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let len = self.bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(self.bytes.len() - start);
+        self.pos = start + len;
+
+        let negative = self.signed && start > 0 && self.bytes[start - 1] == b'-';
+        Some(
+            digits(&self.bytes[start..self.pos], negative)
+                .expect("integer should fit into the requested type"),
+        )
+    }
+}
+
+impl<T: Integer> FusedIterator for Ints<'_, T> {}
+
+/// Returns the integers in `s`, skipping everything between them. A `-` directly in front of the digits negates them.
+///
+/// # Panics
+/// The iterator panics if a number doesn't fit into `T`.
+pub fn ints<T: Integer>(s: &str) -> Ints<'_, T> {
+    Ints {
+        bytes: s.as_bytes(),
+        pos: 0,
+        signed: true,
+        _marker: PhantomData,
+    }
+}
+
+/// Like [`ints`], but treats `-` as a separator, e.g. for ranges like `2-4`.
+pub fn uints<T: Integer>(s: &str) -> Ints<'_, T> {
+    Ints {
+        signed: false,
+        ..ints(s)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Matches `s` against a `template` in which every `{}` stands for a field, returning the text of the fields.
+///
+/// A field extends up to the first occurrence of the text following it in the template, the last field up to the
+/// text the template ends with. Returns `None` if the text around the fields does not match.
+///
+/// # Panics
+/// If the template does not contain exactly `N` fields.
+pub fn template<'a, const N: usize>(template: &str, s: &'a str) -> Option<[&'a str; N]> {
+    assert_eq!(
+        template.matches("{}").count(),
+        N,
+        "template {template:?} should have {N} fields"
+    );
+
+    let mut literals = template.split("{}");
+    let mut rest = s.strip_prefix(literals.next().unwrap_or_default())?;
+    let mut fields = [""; N];
+
+    for (i, literal) in literals.enumerate() {
+        if i + 1 == N {
+            fields[i] = rest.strip_suffix(literal)?;
+            rest = "";
+        } else {
+            let end = rest.find(literal)?;
+            fields[i] = &rest[..end];
+            rest = &rest[end + literal.len()..];
+        }
+    }
+
+    rest.is_empty().then_some(fields)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Returns the lines of `input` without line endings and trailing whitespace, skipping the blank lines at its end.
+///
+/// Use [`str::lines`] if trailing whitespace is part of the puzzle.
+pub fn lines(input: &str) -> impl DoubleEndedIterator<Item = &str> + FusedIterator {
+    input.trim_end().lines().map(str::trim_end)
+}
+
+/// Iterator over the blocks of lines in a string, see [`blocks`].
+#[derive(Debug, Clone)]
+pub struct Blocks<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut start = None;
+        let mut end = 0;
+        let mut offset = 0;
+
+        for line in self.rest.split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                start.get_or_insert(offset);
+                end = offset + line.trim_end().len();
+            } else if start.is_some() {
+                break;
+            }
+            offset += line.len();
+        }
+
+        let block = &self.rest[start?..end];
+        self.rest = &self.rest[end..];
+        Some(block)
+    }
+}
+
+impl FusedIterator for Blocks<'_> {}
+
+/// Returns the blocks of `input` that are separated by blank lines, without the line ending and trailing whitespace
+/// of their last line.
+/// Several blank lines in a row and lines containing only whitespace count as a single separator.
+pub fn blocks(input: &str) -> Blocks<'_> {
+    Blocks { rest: input }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{blocks, int, ints, lines, template, uint, uints};
+
+    #[test]
+    fn parses_integers() {
+        assert_eq!(uint::<u8>("255"), Some(255));
+        assert_eq!(uint::<u8>("256"), None);
+        assert_eq!(uint::<u32>("+1"), None);
+        assert_eq!(uint::<u32>(""), None);
+        assert_eq!(uint::<u64>(b"0042"), Some(42));
+
+        assert_eq!(int::<i8>("-128"), Some(-128));
+        assert_eq!(int::<i8>("128"), None);
+        assert_eq!(int::<i32>("+7"), Some(7));
+        assert_eq!(int::<i32>("-"), None);
+        assert_eq!(int::<i32>("1x"), None);
+        assert_eq!(int::<u32>("-1"), None);
+        assert_eq!(int::<u32>("-0"), Some(0));
+    }
+
+    #[test]
+    fn extracts_integers() {
+        let line = "move 1 from -2 to 3-4";
+        assert_eq!(ints::<i64>(line).collect::<Vec<_>>(), vec![1, -2, 3, -4]);
+        assert_eq!(uints::<u64>(line).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(ints::<i64>("no numbers -").count(), 0);
+        assert_eq!(ints::<i64>("-12").collect::<Vec<_>>(), vec![-12]);
+
+        let sensor = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(
+            ints::<i32>(sensor).collect::<Vec<_>>(),
+            vec![2, -18, -2, 15]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "integer should fit")]
+    fn panics_on_overflow() {
+        ints::<u8>("1 1000").for_each(drop);
+    }
+
+    #[test]
+    fn matches_templates() {
+        let line = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
+        assert_eq!(
+            template(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                line
+            ),
+            Some(["2", "18", "-2", "15"])
+        );
+        assert_eq!(template::<2>("Sensor at x={}, y={}:", line), None);
+        assert_eq!(template("{} {}", "a b c"), Some(["a", "b c"]));
+        assert_eq!(template("[{}]", "[1]"), Some(["1"]));
+        assert_eq!(template::<1>("[{}]", "[1]]"), Some(["1]"]));
+        assert_eq!(template::<1>("[{}]", "[1] "), None);
+        assert_eq!(template::<0>("noop", "noop"), Some([]));
+
+        let [x, y] = template("Sensor at x={}, y={}", "Sensor at x=2, y=-18").unwrap();
+        assert_eq!((int::<i32>(x), int::<i32>(y)), (Some(2), Some(-18)));
+        assert_eq!(
+            template::<2>("Sensor at x={}, y={}", "Beacon at x=2, y=18"),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "should have 2 fields")]
+    fn panics_on_wrong_field_count() {
+        let _ = template::<2>("x={}", "x=1");
+    }
+
+    #[test]
+    fn splits_lines_and_blocks() {
+        let input = "a  \r\nb\r\n\r\n \r\nc\t\r\n\r\n";
+        assert_eq!(
+            lines(input).collect::<Vec<_>>(),
+            vec!["a", "b", "", "", "c"]
+        );
+        assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["a  \r\nb", "c"]);
+        assert_eq!(blocks("\n\n").count(), 0);
+        assert_eq!(blocks("x").collect::<Vec<_>>(), vec!["x"]);
+
+        let calories = "1000\r\n2000\r\n\r\n4000\r\n\r\n\r\n5000\r\n6000\r\n";
+        assert_eq!(
+            blocks(calories).collect::<Vec<_>>(),
+            vec!["1000\r\n2000", "4000", "5000\r\n6000"]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+/// Shortest path searches over implicit graphs, given as a closure that returns the neighbours of a node.
+///
+/// Every search accepts several start nodes, which behaves like a single virtual start connected to all of them.
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Costs of weighted searches, e.g. `u32` or `usize`. `Default` has to be the zero cost.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+/// A goal reached by a search, with its cost and the nodes from a start node to the goal, including both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+impl<N, C> Path<N, C> {
+    /// Returns the node the path ends at.
+    ///
+    /// # Panics
+    /// Never panics, a path contains at least the start node.
+    #[must_use]
+    pub fn goal(&self) -> &N {
+        self.nodes.last().expect("path should not be empty")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node<N, C> {
+    node: N,
+    cost: C,
+    parent: Option<usize>,
+}
+
+/// All nodes reached by a search, with their lowest cost and the node they were reached from.
+#[derive(Debug, Clone)]
+pub struct SearchTree<N, C> {
+    indices: HashMap<N, usize>,
+    nodes: Vec<Node<N, C>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> SearchTree<N, C> {
+    fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            nodes: vec![],
+        }
+    }
+
+    /// Records `node` with `cost` if it wasn't reached before or is now reached cheaper.
+    /// Returns the index of the node if it was updated.
+    fn reach(&mut self, node: N, cost: C, parent: Option<usize>) -> Option<usize> {
+        match self.indices.entry(node) {
+            Entry::Occupied(entry) => {
+                let index = *entry.get();
+                let existing = &mut self.nodes[index];
+                (cost < existing.cost).then(|| {
+                    existing.cost = cost;
+                    existing.parent = parent;
+                    index
+                })
+            }
+            Entry::Vacant(entry) => {
+                let index = self.nodes.len();
+                self.nodes.push(Node {
+                    node: entry.key().clone(),
+                    cost,
+                    parent,
+                });
+                entry.insert(index);
+                Some(index)
+            }
+        }
+    }
+
+    fn path_from_index(&self, mut index: usize) -> Path<N, C> {
+        let cost = self.nodes[index].cost;
+        let mut nodes = vec![self.nodes[index].node.clone()];
+        while let Some(parent) = self.nodes[index].parent {
+            nodes.push(self.nodes[parent].node.clone());
+            index = parent;
+        }
+        nodes.reverse();
+        Path { cost, nodes }
+    }
+
+    /// Returns the lowest cost of reaching `node`, `None` if it wasn't reached.
+    #[must_use]
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.indices.get(node).map(|&i| self.nodes[i].cost)
+    }
+
+    /// Returns the cheapest path from any start node to `node`, `None` if it wasn't reached.
+    #[must_use]
+    pub fn path(&self, node: &N) -> Option<Path<N, C>> {
+        self.indices.get(node).map(|&i| self.path_from_index(i))
+    }
+
+    #[must_use]
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    /// Returns the number of nodes reached, including the start nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns all reached nodes with their cost, in the order they were first reached.
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.nodes.iter().map(|n| (&n.node, n.cost))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Breadth-first search for the closest node matching `is_goal`, where every step costs 1.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (tree, goal) = breadth_first(starts, neighbors, is_goal);
+    goal.map(|index| tree.path_from_index(index))
+}
+
+/// Breadth-first search that visits every node reachable from `starts`.
+pub fn bfs_reach<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+) -> SearchTree<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    breadth_first(starts, neighbors, |_| false).0
+}
+
+fn breadth_first<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (SearchTree<N, usize>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut tree = SearchTree::new();
+    let mut queue: VecDeque<usize> = starts
+        .into_iter()
+        .filter_map(|start| tree.reach(start, 0, None))
+        .collect();
+
+    while let Some(index) = queue.pop_front() {
+        let Node { node, cost, .. } = tree.nodes[index].clone();
+        if is_goal(&node) {
+            return (tree, Some(index));
+        }
+
+        for next in neighbors(&node) {
+            if !tree.contains(&next) {
+                queue.extend(tree.reach(next, cost + 1, Some(index)));
+            }
+        }
+    }
+
+    (tree, None)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Dijkstra's algorithm: finds the cheapest node matching `is_goal`, where `neighbors` returns nodes with the cost of moving there.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (tree, goal) = best_first(starts, neighbors, |_| C::default(), is_goal);
+    goal.map(|index| tree.path_from_index(index))
+}
+
+/// Dijkstra's algorithm that visits every node reachable from `starts`, finding the cheapest cost of each.
+pub fn dijkstra_reach<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+) -> SearchTree<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    best_first(starts, neighbors, |_| C::default(), |_| false).0
+}
+
+/// A* search: like [`dijkstra`], but explores nodes in the order of their cost plus `heuristic`, an estimate of the remaining cost to a goal.
+/// The result is the cheapest path as long as the heuristic never overestimates the remaining cost.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (tree, goal) = best_first(starts, neighbors, heuristic, is_goal);
+    goal.map(|index| tree.path_from_index(index))
+}
+
+fn best_first<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (SearchTree<N, C>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut tree = SearchTree::new();
+    let mut open = BinaryHeap::new();
+
+    for start in starts {
+        let estimate = heuristic(&start);
+        if let Some(index) = tree.reach(start, C::default(), None) {
+            open.push(Reverse((estimate, C::default(), index)));
+        }
+    }
+
+    while let Some(Reverse((_, cost, index))) = open.pop() {
+        // skip stale entries of nodes that were reached cheaper after being queued.
+        if cost > tree.nodes[index].cost {
+            continue;
+        }
+
+        let node = tree.nodes[index].node.clone();
+        if is_goal(&node) {
+            return (tree, Some(index));
+        }
+
+        for (next, step_cost) in neighbors(&node) {
+            let next_cost = cost + step_cost;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next_index) = tree.reach(next, next_cost, Some(index)) {
+                open.push(Reverse((estimate, next_cost, next_index)));
+            }
+        }
+    }
+
+    (tree, None)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{astar, bfs, bfs_reach, dijkstra, dijkstra_reach};
+    use crate::grid::{Grid, Pos};
+
+    fn maze() -> Grid<char> {
+        ["S.#....", "..#.##.", "....#..", "##.##.#", "......E"]
+            .join("\n")
+            .parse()
+            .unwrap()
+    }
+
+    fn open_neighbors(grid: &Grid<char>, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        grid.neighbors4(pos).filter(|&n| grid[n] != '#')
+    }
+
+    #[test]
+    fn finds_shortest_paths() {
+        let grid = maze();
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let goal = grid.position(|&c| c == 'E').unwrap();
+
+        let path = bfs([start], |&p| open_neighbors(&grid, p), |&p| p == goal).unwrap();
+        assert_eq!(path.cost, 10);
+        assert_eq!(path.nodes.len(), 11);
+        assert_eq!(path.nodes[0], start);
+        assert_eq!(*path.goal(), goal);
+        assert!(path
+            .nodes
+            .windows(2)
+            .all(|w| w[0].row.abs_diff(w[1].row) + w[0].col.abs_diff(w[1].col) == 1));
+
+        let weighted = |&p: &Pos| open_neighbors(&grid, p).map(|n| (n, 1));
+        let path = dijkstra([start], weighted, |&p| p == goal).unwrap();
+        assert_eq!(path.cost, 10);
+
+        let manhattan = |p: &Pos| p.row.abs_diff(goal.row) + p.col.abs_diff(goal.col);
+        let path = astar([start], weighted, manhattan, |&p| p == goal).unwrap();
+        assert_eq!(path.cost, 10);
+    }
+
+    #[test]
+    fn searches_implicit_graphs() {
+        // reach 10 from 1 by doubling or adding one.
+        let path = bfs([1], |&n| [n * 2, n + 1], |&n| n == 10).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes, vec![1, 2, 4, 5, 10]);
+    }
+
+    #[test]
+    fn searches_from_multiple_starts() {
+        let grid = maze();
+        let goal = grid.position(|&c| c == 'E').unwrap();
+        let starts = [Pos::new(0, 0), Pos::new(0, 6)];
+
+        let path = bfs(starts, |&p| open_neighbors(&grid, p), |&p| p == goal).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.nodes[0], Pos::new(0, 6));
+    }
+
+    #[test]
+    fn reports_unreachable_goals() {
+        let neighbors = |&n: &u32| (n < 10).then_some(n + 2);
+        assert!(bfs([0], neighbors, |&n| n == 5).is_none());
+        assert_eq!(bfs([0], neighbors, |&n| n == 6).unwrap().cost, 3);
+    }
+
+    #[test]
+    fn prefers_cheaper_paths() {
+        // going 0 -> 1 -> 2 is cheaper than the direct edge, although it takes more steps.
+        let edges = |&n: &u32| match n {
+            0 => vec![(2, 10), (1, 1)],
+            1 => vec![(2, 1)],
+            _ => vec![],
+        };
+
+        let path = dijkstra([0], edges, |&n| n == 2).unwrap();
+        assert_eq!(path.cost, 2);
+        assert_eq!(path.nodes, vec![0, 1, 2]);
+
+        let tree = dijkstra_reach([0], edges);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.cost(&2), Some(2));
+        assert_eq!(tree.path(&2).unwrap().nodes, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn reaches_all_nodes() {
+        let tree = bfs_reach([0_u32], |&n| (n < 10).then_some(n + 1));
+        assert_eq!(tree.len(), 11);
+        assert_eq!(tree.cost(&7), Some(7));
+        assert_eq!(tree.cost(&11), None);
+        assert_eq!(tree.iter().map(|(_, c)| c).max(), Some(10));
+    }
+}
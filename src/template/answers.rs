@@ -0,0 +1,306 @@
+use std::{collections::HashMap, fmt::Display, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{Puzzle, ANSI_BOLD, ANSI_RESET};
+
+static ANSWERS_FILE_PATH: &str = "./data/answers.json";
+
+/// Represents the known-good answers for a single puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub puzzle: Puzzle,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// Represents the known-good answers for a set of puzzles.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+/// The result of checking an answer against the known-good answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// The answer differs from the known-good answer, which is included.
+    Wrong(String),
+    Unknown,
+}
+
+impl Answers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(ANSWERS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns empty answers.
+    pub fn read_from_file() -> Self {
+        let Ok(s) = fs::read_to_string(ANSWERS_FILE_PATH) else {
+            return Answers::default();
+        };
+
+        match Answers::try_from(s) {
+            Ok(answers) => answers,
+            Err(e) => {
+                eprintln!("{e}");
+                Answers::default()
+            }
+        }
+    }
+
+    /// Returns the known-good answer for a part, if any.
+    pub fn get(&self, puzzle: Puzzle, part: u8) -> Option<&str> {
+        let answer = self.data.iter().find(|a| a.puzzle == puzzle)?;
+        match part {
+            1 => answer.part_1.as_deref(),
+            2 => answer.part_2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the known-good answer for a part, overwriting any previous value.
+    pub fn set(&mut self, puzzle: Puzzle, part: u8, value: &str) {
+        let index = match self.data.iter().position(|a| a.puzzle == puzzle) {
+            Some(index) => index,
+            None => {
+                self.data.push(Answer {
+                    puzzle,
+                    part_1: None,
+                    part_2: None,
+                });
+                self.data.sort_unstable_by_key(|a| a.puzzle);
+                self.data.iter().position(|a| a.puzzle == puzzle).unwrap()
+            }
+        };
+
+        let answer = &mut self.data[index];
+        match part {
+            1 => answer.part_1 = Some(value.into()),
+            2 => answer.part_2 = Some(value.into()),
+            _ => {}
+        }
+    }
+
+    /// Checks `value` against the known-good answer for a part.
+    pub fn verdict(&self, puzzle: Puzzle, part: u8, value: Option<&str>) -> Verdict {
+        match (self.get(puzzle, part), value) {
+            (Some(expected), Some(value)) if expected == value => Verdict::Correct,
+            (Some(expected), _) => Verdict::Wrong(expected.into()),
+            (None, _) => Verdict::Unknown,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl Verdict {
+    /// Formats the verdict as a suffix for a printed result.
+    pub fn format(&self) -> String {
+        match self {
+            Verdict::Correct => " ✔".into(),
+            Verdict::Wrong(expected) if expected.contains('\n') => " ✘".into(),
+            Verdict::Wrong(expected) => format!(" ✘ (expected {ANSI_BOLD}{expected}{ANSI_RESET})"),
+            Verdict::Unknown => " ?".into(),
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("correct"),
+            Verdict::Wrong(_) => f.write_str("wrong"),
+            Verdict::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        value.puzzle.insert_json(&mut map);
+
+        map.insert(
+            "part_1".into(),
+            value
+                .part_1
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        map.insert(
+            "part_2".into(),
+            value
+                .part_2
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let puzzle = Puzzle::from_json(json)
+            .ok_or("Expected answer.day to be a Day struct and answer.year a number.")?;
+
+        let part_1 = json
+            .get("part_1")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_1 to be null or string.")?;
+
+        let part_2 = json
+            .get("part_2")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_2 to be null or string.")?;
+
+        Ok(Answer {
+            puzzle,
+            part_1: part_1.cloned(),
+            part_2: part_2.cloned(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answer, Answers, Verdict};
+    use crate::{day, template::Puzzle};
+
+    fn get_mock_answers() -> Answers {
+        Answers {
+            data: vec![Answer {
+                puzzle: day!(1).into(),
+                part_1: Some("24000".into()),
+                part_2: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn handles_json_answers() {
+        let json =
+            r#"{ "data": [{ "day": "01", "part_1": "24000", "part_2": null }] }"#.to_string();
+        let answers = Answers::try_from(json).unwrap();
+        assert_eq!(answers.data, get_mock_answers().data);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_answers() {
+        let json = r#"{ "data": [{ "day": "26", "part_1": null, "part_2": null }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+
+    #[test]
+    fn computes_verdicts() {
+        let answers = get_mock_answers();
+        assert_eq!(
+            answers.verdict(day!(1).into(), 1, Some("24000")),
+            Verdict::Correct
+        );
+        assert_eq!(
+            answers.verdict(day!(1).into(), 1, Some("24001")),
+            Verdict::Wrong("24000".into())
+        );
+        assert_eq!(
+            answers.verdict(day!(1).into(), 1, None),
+            Verdict::Wrong("24000".into())
+        );
+        assert_eq!(
+            answers.verdict(day!(1).into(), 2, Some("1")),
+            Verdict::Unknown
+        );
+        assert_eq!(
+            answers.verdict(day!(2).into(), 1, Some("1")),
+            Verdict::Unknown
+        );
+    }
+
+    #[test]
+    fn sets_answers() {
+        let mut answers = get_mock_answers();
+        answers.set(day!(3).into(), 2, "abc");
+        answers.set(day!(1).into(), 2, "45000");
+        answers.set(day!(1).into(), 1, "1");
+
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.get(day!(1).into(), 1), Some("1"));
+        assert_eq!(answers.get(day!(1).into(), 2), Some("45000"));
+        assert_eq!(answers.get(day!(3).into(), 1), None);
+        assert_eq!(answers.get(day!(3).into(), 2), Some("abc"));
+    }
+
+    #[test]
+    fn separates_years() {
+        let mut answers = get_mock_answers();
+        answers.set(Puzzle::new(2015, day!(1)), 1, "1");
+
+        assert_eq!(answers.get(day!(1).into(), 1), Some("24000"));
+        assert_eq!(answers.get(Puzzle::new(2015, day!(1)), 1), Some("1"));
+        assert_eq!(answers.data[0].puzzle.year, 2015);
+    }
+
+    #[test]
+    fn roundtrips_answers() {
+        let answers = get_mock_answers();
+        let json = tinyjson::JsonValue::from(answers.clone())
+            .stringify()
+            .unwrap();
+        assert_eq!(Answers::try_from(json).unwrap().data, answers.data);
+    }
+}
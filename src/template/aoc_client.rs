@@ -0,0 +1,557 @@
+/// Native client for the advent of code website. Replaces the "aoc-cli" command-line by default.
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::template::{
+    aoc_cli::{get_input_path, get_puzzle_path},
+    Puzzle,
+};
+
+static DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+static SESSION_FILE_NAME: &str = ".adventofcode.session";
+static USER_AGENT: &str = concat!(
+    "github.com/fspoettel/advent-of-code-rust ",
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION")
+);
+
+/// Selects how the template talks to advent of code. Configured via `AOC_BACKEND`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Built-in HTTP client.
+    #[default]
+    Native,
+    /// Shells out to an installed "aoc-cli".
+    AocCli,
+}
+
+impl Backend {
+    /// Reads the backend from `AOC_BACKEND`, defaulting to the native client.
+    pub fn from_env() -> Self {
+        match env::var("AOC_BACKEND").as_deref() {
+            Ok("aoc-cli" | "aoc_cli" | "aoc") => Backend::AocCli,
+            Ok("native" | "") | Err(_) => Backend::Native,
+            Ok(other) => {
+                eprintln!("Unknown AOC_BACKEND \"{other}\", falling back to the native client.");
+                Backend::Native
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug)]
+pub enum AocClientError {
+    SessionNotFound,
+    /// The server responded with a non-success status code.
+    BadStatus(u16),
+    /// The request failed before a response was received.
+    Transport(String),
+    Io(std::io::Error),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set AOC_SESSION or create the file \"~/{SESSION_FILE_NAME}\"."
+            ),
+            AocClientError::BadStatus(400 | 401) => write!(
+                f,
+                "advent of code rejected the request. Your session cookie might have expired."
+            ),
+            AocClientError::BadStatus(404) => write!(
+                f,
+                "advent of code returned 404. The puzzle might not be unlocked yet."
+            ),
+            AocClientError::BadStatus(status) => {
+                write!(f, "advent of code responded with status {status}.")
+            }
+            AocClientError::Transport(e) => write!(f, "request failed: {e}"),
+            AocClientError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<ureq::Error> for AocClientError {
+    fn from(value: ureq::Error) -> Self {
+        match value {
+            ureq::Error::Status(status, _) => AocClientError::BadStatus(status),
+            ureq::Error::Transport(e) => AocClientError::Transport(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for AocClientError {
+    fn from(value: std::io::Error) -> Self {
+        AocClientError::Io(value)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        AocClient {
+            agent,
+            base_url: base_url.trim_end_matches('/').into(),
+            session: session.trim().into(),
+        }
+    }
+
+    /// Creates a client from the environment.
+    /// The base url can be overridden with `AOC_BASE_URL`, e.g. to point it at a mock server.
+    pub fn from_env() -> Result<Self, AocClientError> {
+        let session = read_session()?;
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        Ok(AocClient::new(&base_url, &session))
+    }
+
+    /// Returns the input of a puzzle.
+    pub fn input(&self, puzzle: Puzzle) -> Result<String, AocClientError> {
+        self.get(&format!("{}/input", puzzle_path(puzzle)))
+    }
+
+    /// Returns the description of a puzzle, converted to markdown.
+    pub fn puzzle(&self, puzzle: Puzzle) -> Result<String, AocClientError> {
+        let html = self.get(&puzzle_path(puzzle))?;
+        Ok(html_to_markdown(&html))
+    }
+
+    /// Submits an answer and returns the response message, converted to markdown.
+    pub fn submit(&self, puzzle: Puzzle, part: u8, answer: &str) -> Result<String, AocClientError> {
+        let url = format!("{}{}/answer", self.base_url, puzzle_path(puzzle));
+        let html = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?
+            .into_string()?;
+        Ok(html_to_markdown(&html))
+    }
+
+    /// Downloads input and puzzle description to their files in `data/`.
+    pub fn download(&self, puzzle: Puzzle) -> Result<(), AocClientError> {
+        let input_path = get_input_path(puzzle);
+        let puzzle_path = get_puzzle_path(puzzle);
+
+        fs::write(&input_path, self.input(puzzle)?)?;
+        fs::write(&puzzle_path, self.puzzle(puzzle)?)?;
+
+        println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+        println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+        Ok(())
+    }
+
+    /// Prints the puzzle description and refreshes its file in `data/puzzles`.
+    pub fn read(&self, puzzle: Puzzle) -> Result<(), AocClientError> {
+        let description = self.puzzle(puzzle)?;
+        fs::write(get_puzzle_path(puzzle), &description)?;
+        println!("{description}");
+        Ok(())
+    }
+
+    fn get(&self, path: &str) -> Result<String, AocClientError> {
+        Ok(self
+            .agent
+            .get(&format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .call()?
+            .into_string()?)
+    }
+}
+
+fn puzzle_path(puzzle: Puzzle) -> String {
+    format!("/{}/day/{}", puzzle.year, puzzle.day.into_inner())
+}
+
+/// Reads the session cookie from `AOC_SESSION`, the file at `AOC_SESSION_FILE`,
+/// or `~/.adventofcode.session` (the same file aoc-cli uses), in this order.
+fn read_session() -> Result<String, AocClientError> {
+    if let Ok(session) = env::var("AOC_SESSION") {
+        if !session.trim().is_empty() {
+            return Ok(session);
+        }
+    }
+
+    let candidates: Vec<PathBuf> = match env::var_os("AOC_SESSION_FILE") {
+        Some(path) => vec![path.into()],
+        None => env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home| {
+                let home = Path::new(&home);
+                vec![
+                    home.join(SESSION_FILE_NAME),
+                    home.join(".config").join("adventofcode.session"),
+                ]
+            })
+            .unwrap_or_default(),
+    };
+
+    candidates
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+        .ok_or(AocClientError::SessionNotFound)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Converts the `<article>` elements of an advent of code page to markdown.
+/// Handles the small subset of HTML the site uses; pages without articles are converted as a whole.
+pub fn html_to_markdown(html: &str) -> String {
+    let articles = extract_elements(html, "article");
+
+    let content = if articles.is_empty() {
+        vec![extract_elements(html, "main")
+            .into_iter()
+            .next()
+            .unwrap_or(html)]
+    } else {
+        articles
+    };
+
+    let markdown: Vec<String> = content.into_iter().map(convert).collect();
+    tidy(&markdown.join("\n\n"))
+}
+
+/// Returns the inner HTML of all (non-nested) elements with a given tag name.
+fn extract_elements<'a>(html: &'a str, name: &str) -> Vec<&'a str> {
+    let open = format!("<{name}");
+    let close = format!("</{name}>");
+    let mut elements = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // make sure we matched e.g. `<article>` and not `<articles>`.
+        if !after.starts_with(['>', ' ', '\n', '\t']) {
+            rest = after;
+            continue;
+        }
+        let Some(inner_start) = after.find('>') else {
+            break;
+        };
+        let inner = &after[inner_start + 1..];
+        let Some(end) = inner.find(&close) else {
+            break;
+        };
+        elements.push(&inner[..end]);
+        rest = &inner[end + close.len()..];
+    }
+
+    elements
+}
+
+fn convert(html: &str) -> String {
+    let mut out = String::new();
+    let mut links: Vec<String> = vec![];
+    let mut in_pre = false;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(tag_start) = rest.strip_prefix('<') {
+            let end = tag_start.find('>').unwrap_or(tag_start.len());
+            let tag = &tag_start[..end];
+            rest = tag_start.get(end + 1..).unwrap_or("");
+
+            let is_closing = tag.starts_with('/');
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase();
+
+            match (name.as_str(), is_closing) {
+                ("h1" | "h2" | "h3", false) => {
+                    start_block(&mut out);
+                    out.push_str("## ");
+                }
+                ("h1" | "h2" | "h3" | "p" | "ul", true) => out.push_str("\n\n"),
+                ("p" | "ul", false) => start_block(&mut out),
+                ("pre", false) => {
+                    start_block(&mut out);
+                    out.push_str("```\n");
+                    in_pre = true;
+                }
+                ("pre", true) => {
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```\n\n");
+                    in_pre = false;
+                }
+                ("code", _) if !in_pre => out.push('`'),
+                ("em", _) => out.push('*'),
+                ("li", false) => out.push_str("- "),
+                ("li", true) | ("br", _) => out.push('\n'),
+                ("a", false) => {
+                    links.push(attribute(tag, "href").unwrap_or_default().into());
+                    out.push('[');
+                }
+                ("a", true) => {
+                    let href = links.pop().unwrap_or_default();
+                    out.push_str(&format!("]({href})"));
+                }
+                _ => {}
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = decode_entities(&rest[..end]);
+            rest = &rest[end..];
+
+            if in_pre {
+                out.push_str(&text);
+            } else {
+                for c in text.chars() {
+                    if !c.is_whitespace() {
+                        out.push(c);
+                    } else if !out.is_empty() && !out.ends_with([' ', '\n']) {
+                        out.push(' ');
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn start_block(out: &mut String) {
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("{name}=\"");
+    let start = tag.find(&key)? + key.len();
+    let end = start + tag[start..].find('"')?;
+    Some(&tag[start..end])
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let c = match &rest[1..end] {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                entity => {
+                    let code = entity.strip_prefix('#')?;
+                    let code = match code.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => code.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+
+        if let Some((c, end)) = decoded {
+            out.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Strips trailing whitespace and collapses runs of blank lines.
+fn tidy(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut blank_lines = 0;
+
+    for line in markdown.trim().lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{html_to_markdown, AocClient, AocClientError};
+    use crate::{day, template::Puzzle};
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    /// Serves a single request with `status` and `body`, returning the raw request.
+    fn mock_server(status: u16, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((key, value)) = line.split_once(':') {
+                    if key.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+
+            let mut body_buf = vec![0; content_length];
+            reader.read_exact(&mut body_buf).unwrap();
+            request.push_str(&String::from_utf8(body_buf).unwrap());
+
+            let response = format!(
+                "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn downloads_input() {
+        let (url, server) = mock_server(200, "1000\n2000\n");
+        let client = AocClient::new(&url, "abc\n");
+
+        assert_eq!(
+            client.input(Puzzle::new(2022, day!(1))).unwrap(),
+            "1000\n2000\n"
+        );
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2022/day/1/input HTTP/1.1"));
+        assert!(request.contains("session=abc\r\n"));
+    }
+
+    #[test]
+    fn fetches_puzzles() {
+        let (url, server) = mock_server(
+            200,
+            r#"<html><main><article class="day-desc"><h2>--- Day 1 ---</h2><p>Count <em>calories</em>.</p></article></main></html>"#,
+        );
+        let client = AocClient::new(&url, "abc");
+
+        assert_eq!(
+            client.puzzle(Puzzle::new(2022, day!(1))).unwrap(),
+            "## --- Day 1 ---\n\nCount *calories*.\n"
+        );
+        assert!(server
+            .join()
+            .unwrap()
+            .starts_with("GET /2022/day/1 HTTP/1.1"));
+    }
+
+    #[test]
+    fn submits_answers() {
+        let (url, server) = mock_server(
+            200,
+            "<main><article><p>That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.</p></article></main>",
+        );
+        let client = AocClient::new(&url, "abc");
+
+        assert_eq!(
+            client
+                .submit(Puzzle::new(2021, day!(1)), 2, "24000")
+                .unwrap(),
+            "That's the right answer! You are one gold star closer.\n"
+        );
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2021/day/1/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=24000"));
+    }
+
+    #[test]
+    fn reports_bad_status() {
+        let (url, server) = mock_server(404, "Not Found");
+        let client = AocClient::new(&url, "abc");
+
+        assert!(matches!(
+            client.input(Puzzle::new(2022, day!(25))),
+            Err(AocClientError::BadStatus(404))
+        ));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn converts_html_to_markdown() {
+        let html = r#"<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2><p>Santa's reindeer &amp; elves
+eat <a href="/2022/about" target="_blank">snacks</a>:</p>
+<pre><code>1000
+&lt;2000&gt;
+</code></pre>
+<ul>
+<li>The <code>first</code> Elf.</li>
+<li>The second Elf.</li>
+</ul>
+</article><article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>More.</p></article>"#;
+
+        assert_eq!(
+            html_to_markdown(html),
+            "## --- Day 1: Calorie Counting ---\n\n\
+            Santa's reindeer & elves eat [snacks](/2022/about):\n\n\
+            ```\n1000\n<2000>\n```\n\n\
+            - The `first` Elf.\n\
+            - The second Elf.\n\n\
+            ## --- Part Two ---\n\n\
+            More.\n"
+        );
+    }
+}
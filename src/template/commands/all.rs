@@ -1,5 +1,31 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::time::Duration;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::{
+    registry::Registry,
+    run_multi::{run_multi, RunOptions},
+    OutputFormat, Puzzle,
+};
+
+/// Time after which a day is considered stuck, if not overridden with `--timeout`.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn handle(
+    registry: &Registry,
+    year: u16,
+    is_release: bool,
+    format: OutputFormat,
+    jobs: usize,
+    timeout: Option<Duration>,
+) {
+    run_multi(
+        registry,
+        &Puzzle::all_of_year(year).collect(),
+        RunOptions {
+            is_release,
+            format,
+            jobs,
+            timeout,
+            ..RunOptions::default()
+        },
+    );
 }
@@ -1,13 +1,23 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::aoc_client::{AocClient, Backend};
+use crate::template::{aoc_cli, Puzzle};
 use std::process;
 
-pub fn handle(day: Day) {
+pub fn handle(puzzle: Puzzle) {
+    if Backend::from_env() == Backend::Native {
+        let result = AocClient::from_env().and_then(|client| client.download(puzzle));
+        if let Err(e) = result {
+            eprintln!("failed to download {}: {e}", puzzle.title());
+            process::exit(1);
+        }
+        return;
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
+    if let Err(e) = aoc_cli::download(puzzle) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
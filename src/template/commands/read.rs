@@ -1,14 +1,24 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::aoc_client::{AocClient, Backend};
+use crate::template::{aoc_cli, Puzzle};
+
+pub fn handle(puzzle: Puzzle) {
+    if Backend::from_env() == Backend::Native {
+        let result = AocClient::from_env().and_then(|client| client.read(puzzle));
+        if let Err(e) = result {
+            eprintln!("failed to read {}: {e}", puzzle.title());
+            process::exit(1);
+        }
+        return;
+    }
 
-pub fn handle(day: Day) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::read(day) {
+    if let Err(e) = aoc_cli::read(puzzle) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
@@ -4,7 +4,7 @@ use std::{
     process,
 };
 
-use crate::template::Day;
+use crate::template::Puzzle;
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
@@ -14,13 +14,18 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+pub fn handle(puzzle: Puzzle) {
+    let name = puzzle.name();
+    let input_path = format!("data/inputs/{name}.txt");
+    let example_path = format!("data/examples/{name}.txt");
+    let module_path = format!("src/bin/{name}.rs");
 
     let mut file = match safe_create_file(&module_path) {
         Ok(file) => file,
@@ -32,7 +37,7 @@ pub fn handle(day: Day) {
 
     match file.write_all(
         MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
+            .replace("%SOLUTION_ARGS%", &solution_args(puzzle))
             .as_bytes(),
     ) {
         Ok(()) => {
@@ -65,5 +70,26 @@ pub fn handle(day: Day) {
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    println!(
+        "🎄 Type `cargo solve {}` to run your solution.",
+        solve_args(puzzle)
+    );
+}
+
+/// Arguments of the `solution!` macro for a puzzle, e.g. `1` or `1, year = 2021`.
+fn solution_args(puzzle: Puzzle) -> String {
+    let day = puzzle.day.into_inner();
+    if puzzle.is_default_year() {
+        day.to_string()
+    } else {
+        format!("{day}, year = {}", puzzle.year)
+    }
+}
+
+fn solve_args(puzzle: Puzzle) -> String {
+    if puzzle.is_default_year() {
+        puzzle.day.to_string()
+    } else {
+        format!("{} --year {}", puzzle.day, puzzle.year)
+    }
 }
@@ -1,9 +1,15 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::{OutputFormat, Puzzle};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+pub fn handle(
+    puzzle: Puzzle,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    format: OutputFormat,
+) {
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), puzzle.name()];
 
     if dhat {
         cmd_args.extend([
@@ -18,6 +24,9 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
 
     cmd_args.push("--".to_string());
 
+    cmd_args.push("--format".to_string());
+    cmd_args.push(format.to_string());
+
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
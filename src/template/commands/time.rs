@@ -1,40 +1,183 @@
 use std::collections::HashSet;
+use std::process;
 
-use crate::template::run_multi::run_multi;
+use crate::template::history::{Comparison, History, Run};
+use crate::template::registry::Registry;
+use crate::template::run_multi::{run_multi, RunOptions};
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{readme_benchmarks, OutputFormat, Puzzle, ANSI_BOLD, ANSI_RESET};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+/// Default threshold for `--compare`, in percent.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 10.0;
+
+/// Benches solutions. If `compare` is set, medians are compared to the last stored run on this machine,
+/// and the process exits with an error if a part regressed by more than `compare` percent.
+pub fn handle(
+    registry: &Registry,
+    year: u16,
+    puzzle: Option<Puzzle>,
+    run_all: bool,
+    store: bool,
+    compare: Option<f64>,
+    format: OutputFormat,
+) {
     let stored_timings = Timings::read_from_file();
 
-    let days_to_run = day.map_or_else(
+    let puzzles_to_run = puzzle.map_or_else(
         || {
-            if run_all {
-                all_days().collect()
+            // comparisons need fresh timings of every day.
+            if run_all || compare.is_some() {
+                Puzzle::all_of_year(year).collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                Puzzle::all_of_year(year)
+                    .filter(|puzzle| !stored_timings.is_day_complete(*puzzle))
                     .collect()
             }
         },
-        |day| HashSet::from([day]),
+        |puzzle| HashSet::from([puzzle]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(
+        registry,
+        &puzzles_to_run,
+        RunOptions {
+            is_release: true,
+            is_timed: true,
+            format,
+            ..RunOptions::default()
+        },
+    )
+    .timings
+    .unwrap();
+
+    let mut history = History::read_from_file();
+    let run = Run::new(&timings);
+
+    let regressions = compare.map_or(0, |threshold| {
+        let comparisons = history.compare(&run.machine, &timings);
+        print_comparisons(&comparisons, &run.machine, threshold, format)
+    });
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
         merged_timings.store_file().unwrap();
 
-        println!();
+        history.data.push(run);
+        if let Err(e) = history.store_file() {
+            eprintln!("Failed to store benchmark history: {e}");
+        }
+
         match readme_benchmarks::update(merged_timings) {
-            Ok(()) => {
-                println!("Stored updated benchmarks.");
+            Ok(()) => match format {
+                OutputFormat::Text => println!("\nStored updated benchmarks."),
+                // keep stdout machine-readable.
+                OutputFormat::Json => eprintln!("Stored updated benchmarks."),
+            },
+            Err(e) => {
+                eprintln!("Failed to store updated benchmarks: {e}");
             }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
+        }
+    }
+
+    if regressions > 0 {
+        process::exit(1);
+    }
+}
+
+/// Runs solutions once with DHAT and captures the heap usage of every part.
+/// With `store`, the memory is merged into the stored timings, durations of the profiled builds are discarded.
+pub fn handle_dhat(
+    registry: &Registry,
+    year: u16,
+    puzzle: Option<Puzzle>,
+    store: bool,
+    format: OutputFormat,
+) {
+    let puzzles_to_run = puzzle.map_or_else(
+        || Puzzle::all_of_year(year).collect(),
+        |puzzle| HashSet::from([puzzle]),
+    );
+
+    let timings = run_multi(
+        registry,
+        &puzzles_to_run,
+        RunOptions {
+            is_release: true,
+            is_dhat: true,
+            format,
+            ..RunOptions::default()
+        },
+    )
+    .timings
+    .unwrap();
+
+    if store {
+        let merged_timings = Timings::read_from_file().merge_memory(&timings);
+        merged_timings.store_file().unwrap();
+
+        match readme_benchmarks::update(merged_timings) {
+            Ok(()) => match format {
+                OutputFormat::Text => println!("\nStored updated memory usage."),
+                // keep stdout machine-readable.
+                OutputFormat::Json => eprintln!("Stored updated memory usage."),
+            },
+            Err(e) => {
+                eprintln!("Failed to store updated memory usage: {e}");
             }
         }
     }
 }
+
+/// Prints every comparison and returns the number of regressions.
+fn print_comparisons(
+    comparisons: &[Comparison],
+    machine: &str,
+    threshold: f64,
+    format: OutputFormat,
+) -> usize {
+    let mut lines = vec![format!(
+        "\n{ANSI_BOLD}Compared to previous runs on \"{machine}\":{ANSI_RESET}"
+    )];
+
+    for comparison in comparisons {
+        let marker = if comparison.is_regression(threshold) {
+            " ✘ regression"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "{} Part {}: {:.1?} → {:.1?} ({:+.1}%, baseline {}){marker}",
+            comparison.puzzle.title(),
+            comparison.part,
+            comparison.baseline,
+            comparison.current,
+            comparison.change_percent(),
+            comparison.baseline_revision.as_deref().unwrap_or("unknown"),
+        ));
+    }
+
+    let regressions = comparisons
+        .iter()
+        .filter(|c| c.is_regression(threshold))
+        .count();
+
+    if comparisons.is_empty() {
+        lines.push(
+            "No previous runs to compare to. Store one with `cargo time --all --store`.".into(),
+        );
+    } else if regressions == 0 {
+        lines.push(format!("No regressions beyond {threshold}%."));
+    } else {
+        lines.push(format!("{regressions} regression(s) beyond {threshold}%."));
+    }
+
+    let output = lines.join("\n");
+    match format {
+        OutputFormat::Text => println!("{output}"),
+        // keep stdout machine-readable.
+        OutputFormat::Json => eprintln!("{output}"),
+    }
+
+    regressions
+}
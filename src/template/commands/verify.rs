@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use std::process;
+use std::time::Duration;
+
+use crate::template::answers::{Answers, Verdict};
+use crate::template::registry::Registry;
+use crate::template::run_multi::{run_multi, RunOptions};
+use crate::template::{Puzzle, ANSI_BOLD, ANSI_RESET};
+
+pub fn handle(
+    registry: &Registry,
+    year: u16,
+    puzzle: Option<Puzzle>,
+    is_release: bool,
+    store: bool,
+    timeout: Option<Duration>,
+) {
+    let puzzles_to_run = puzzle.map_or_else(
+        || Puzzle::all_of_year(year).collect(),
+        |puzzle| HashSet::from([puzzle]),
+    );
+
+    let records = run_multi(
+        registry,
+        &puzzles_to_run,
+        RunOptions {
+            is_release,
+            timeout,
+            ..RunOptions::default()
+        },
+    )
+    .records
+    .into_iter()
+    // a failed parser counts as a failure, since its parts are not run.
+    .filter(|r| !r.is_parse() || r.failure.is_some())
+    .collect::<Vec<_>>();
+
+    // failed parts have no answer to verify, they are counted separately.
+    let failed = records.iter().filter(|r| r.failure.is_some()).count();
+    let count = |f: fn(&Verdict) -> bool| {
+        records
+            .iter()
+            .filter(|r| r.failure.is_none() && f(&r.verdict))
+            .count()
+    };
+    let correct = count(|v| *v == Verdict::Correct);
+    let wrong = count(|v| matches!(v, Verdict::Wrong(_)));
+    let unknown = count(|v| *v == Verdict::Unknown);
+
+    let failed_str = if failed > 0 {
+        format!(", {failed} failed")
+    } else {
+        String::new()
+    };
+
+    println!(
+        "\n{ANSI_BOLD}Verified:{ANSI_RESET} {correct} correct, {wrong} wrong, {unknown} unknown{failed_str}"
+    );
+
+    if store && unknown > 0 {
+        let mut answers = Answers::read_from_file();
+        let mut stored = 0;
+
+        // parts without an answer have nothing to store.
+        for r in records
+            .iter()
+            .filter(|r| r.failure.is_none() && r.verdict == Verdict::Unknown)
+        {
+            if let Some(answer) = &r.answer {
+                answers.set(r.puzzle, r.part, answer);
+                stored += 1;
+            }
+        }
+
+        match answers.store_file() {
+            Ok(()) => println!("Stored {stored} new answer(s)."),
+            Err(e) => {
+                eprintln!("Failed to store answers: {e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    if wrong > 0 || failed > 0 {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,399 @@
+/// Errors of solution parts and parsers, and how they are reported.
+use std::{
+    any::Any,
+    cell::Cell,
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+    time::Duration,
+};
+use tinyjson::JsonValue;
+
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// An error caused by a specific line of the puzzle input.
+///
+/// When a part fails with an error that has a `LineError` in its chain, the offending line is printed with the error.
+#[derive(Debug)]
+pub struct LineError {
+    /// The line number, starting at 1.
+    pub line: usize,
+    pub content: String,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl LineError {
+    /// Creates an error for the line at `index`, as returned by `input.lines().enumerate()`.
+    pub fn new(
+        index: usize,
+        content: &str,
+        source: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            line: index + 1,
+            content: content.to_string(),
+            source: source.into(),
+        }
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid input on line {}", self.line)
+    }
+}
+
+impl Error for LineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Adds the offending input line to the error of a result.
+/// Call it with the index of `input.lines().enumerate()`, the reported line number starts at 1.
+pub trait LineContext<T> {
+    fn line_context(self, index: usize, line: &str) -> Result<T, LineError>;
+}
+
+impl<T, E: Error + Send + Sync + 'static> LineContext<T> for Result<T, E> {
+    fn line_context(self, index: usize, line: &str) -> Result<T, LineError> {
+        self.map_err(|e| LineError::new(index, line, e))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// How a part or parser failed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Returned an error.
+    #[default]
+    Error,
+    /// Panicked, the panic was caught and the remaining parts and days were run.
+    Panic,
+    /// Did not finish within the timeout of `cargo all` or `cargo verify`.
+    Timeout,
+}
+
+impl FailureKind {
+    /// Label of the failure, printed next to the failed part.
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureKind::Error => "failed",
+            FailureKind::Panic => "panicked",
+            FailureKind::Timeout => "timed out",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            FailureKind::Error => "error",
+            FailureKind::Panic => "panic",
+            FailureKind::Timeout => "timeout",
+        }
+    }
+}
+
+/// A failed part or parser: the chain of error messages and the offending input line, if known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub kind: FailureKind,
+    /// The error message followed by the messages of its sources.
+    pub chain: Vec<String>,
+    /// Line number and content of the input line that caused the error.
+    pub line: Option<(usize, String)>,
+}
+
+impl Failure {
+    /// Walks the source chain of `error`, picking up the input line of the first [`LineError`].
+    pub fn from_error(error: &(dyn Error + 'static)) -> Self {
+        let mut chain = vec![];
+        let mut line = None;
+        let mut current = Some(error);
+
+        while let Some(e) = current {
+            chain.push(e.to_string());
+            if let (None, Some(line_error)) = (&line, e.downcast_ref::<LineError>()) {
+                line = Some((line_error.line, line_error.content.clone()));
+            }
+            current = e.source();
+        }
+
+        Self {
+            kind: FailureKind::Error,
+            chain,
+            line,
+        }
+    }
+
+    /// Creates the failure of a caught panic from its payload and, if known, the location it occurred at.
+    pub fn from_panic(payload: &(dyn Any + Send), location: Option<&str>) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".into());
+
+        let message = match location {
+            Some(location) => format!("{message} ({location})"),
+            None => message,
+        };
+
+        Self {
+            kind: FailureKind::Panic,
+            chain: vec![message],
+            line: None,
+        }
+    }
+
+    /// Creates the failure of a part that did not finish within `timeout`.
+    pub fn timed_out(timeout: Duration) -> Self {
+        Self {
+            kind: FailureKind::Timeout,
+            chain: vec![format!("did not finish within {timeout:?}")],
+            line: None,
+        }
+    }
+
+    /// Formats the error chain and input line as indented lines, including a trailing newline.
+    pub fn format(&self) -> String {
+        let mut lines = vec![];
+
+        for (i, message) in self.chain.iter().enumerate() {
+            if i == 0 {
+                let kind = self.kind.as_str();
+                lines.push(format!("  {ANSI_BOLD}{kind}:{ANSI_RESET} {message}"));
+            } else {
+                lines.push(format!("  caused by: {message}"));
+            }
+        }
+
+        if let Some((number, content)) = &self.line {
+            lines.push(format!("  --> line {number}: {content}"));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.chain.join(": "))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    static IS_CATCHING: Cell<bool> = const { Cell::new(false) };
+    static PANIC_LOCATION: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, turning a panic into a [`Failure`] instead of unwinding further.
+///
+/// While catching, the panic message is not printed, since it's reported with the failed part.
+/// Panics outside of `catch_panic` and on other threads are printed as usual.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, Failure> {
+    PANIC_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if IS_CATCHING.get() {
+                PANIC_LOCATION.set(info.location().map(ToString::to_string));
+            } else {
+                default_hook(info);
+            }
+        }));
+    });
+
+    let was_catching = IS_CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    IS_CATCHING.set(was_catching);
+
+    result
+        .map_err(|payload| Failure::from_panic(payload.as_ref(), PANIC_LOCATION.take().as_deref()))
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Failure> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Failure) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("kind".into(), JsonValue::String(value.kind.as_str().into()));
+        map.insert(
+            "chain".into(),
+            JsonValue::Array(
+                value
+                    .chain
+                    .iter()
+                    .map(|m| JsonValue::String(m.clone()))
+                    .collect(),
+            ),
+        );
+        map.insert(
+            "line".into(),
+            value
+                .line
+                .as_ref()
+                .map_or(JsonValue::Null, |(n, _)| JsonValue::Number(*n as f64)),
+        );
+        map.insert(
+            "content".into(),
+            value
+                .line
+                .as_ref()
+                .map_or(JsonValue::Null, |(_, c)| JsonValue::String(c.clone())),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Failure {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected error to be a JSON object.")?;
+
+        // records written before panics and timeouts were caught have no kind.
+        let kind = match json.get("kind").and_then(|v| v.get::<String>()) {
+            None => FailureKind::Error,
+            Some(kind) => [FailureKind::Error, FailureKind::Panic, FailureKind::Timeout]
+                .into_iter()
+                .find(|k| k.as_str() == kind)
+                .ok_or("Expected error.kind to be one of error, panic or timeout.")?,
+        };
+
+        let chain = json
+            .get("chain")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("Expected error.chain to be an array.")?
+            .iter()
+            .map(|m| m.get::<String>().cloned())
+            .collect::<Option<_>>()
+            .ok_or("Expected error.chain to contain strings.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let number = json
+            .get("line")
+            .and_then(|v| v.get::<f64>())
+            .map(|n| *n as usize);
+        let content = json.get("content").and_then(|v| v.get::<String>());
+
+        Ok(Failure {
+            kind,
+            chain,
+            line: number.zip(content.cloned()),
+        })
+    }
+}
+
+/// Parses an error object that might be missing or null.
+pub(crate) fn parse_optional_failure(value: Option<&JsonValue>) -> Result<Option<Failure>, String> {
+    match value {
+        Some(v) if !v.is_null() => Failure::try_from(v).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{catch_panic, Failure, FailureKind, LineContext};
+    use std::{num::ParseIntError, time::Duration};
+
+    #[derive(Debug)]
+    struct ParseError(Box<dyn std::error::Error + Send + Sync>);
+
+    impl std::fmt::Display for ParseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("could not parse input")
+        }
+    }
+
+    impl std::error::Error for ParseError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(self.0.as_ref())
+        }
+    }
+
+    fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| line.parse().line_context(i, line))
+            .collect::<Result<_, _>>()
+            .map_err(|e| ParseError(Box::new(e)))
+    }
+
+    #[test]
+    fn adds_line_context() {
+        let error = "1\nx\n3"
+            .lines()
+            .enumerate()
+            .map(|(i, line)| line.parse::<u32>().line_context(i, line))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+
+        assert_eq!(error.line, 2);
+        assert_eq!(error.content, "x");
+        assert_eq!(error.to_string(), "invalid input on line 2");
+    }
+
+    #[test]
+    fn collects_error_chain_and_line() {
+        let error = parse("1\n2\nthree").unwrap_err();
+        let failure = Failure::from_error(&error);
+
+        assert_eq!(
+            failure.chain,
+            vec![
+                "could not parse input".to_string(),
+                "invalid input on line 3".to_string(),
+                "three".parse::<u32>().unwrap_err().to_string(),
+            ]
+        );
+        assert_eq!(failure.line, Some((3, "three".into())));
+        assert!(failure.format().ends_with("  --> line 3: three\n"));
+    }
+
+    #[test]
+    fn handles_errors_without_line() {
+        let error: ParseIntError = "x".parse::<u8>().unwrap_err();
+        let failure = Failure::from_error(&error);
+        assert_eq!(failure.chain.len(), 1);
+        assert_eq!(failure.line, None);
+    }
+
+    #[test]
+    fn roundtrips_failures() {
+        for failure in [
+            Failure::from_error(&parse("x").unwrap_err()),
+            Failure::timed_out(Duration::from_secs(1)),
+        ] {
+            let json = tinyjson::JsonValue::from(&failure);
+            assert_eq!(Failure::try_from(&json).unwrap(), failure);
+        }
+    }
+
+    #[test]
+    fn catches_panics() {
+        assert_eq!(catch_panic(|| 1), Ok(1));
+
+        let failure = catch_panic(|| -> u32 { panic!("oh no {}", 1) }).unwrap_err();
+        assert_eq!(failure.kind, FailureKind::Panic);
+        assert!(failure.chain[0].starts_with("oh no 1 (src/template/failure.rs:"));
+        assert!(failure.format().contains("panic:"));
+
+        let failure = catch_panic(|| -> u32 { std::panic::panic_any(0) }).unwrap_err();
+        assert!(failure.chain[0].starts_with("Box<dyn Any>"));
+    }
+}
@@ -0,0 +1,378 @@
+/// Module that keeps a history of benchmark runs and detects regressions between them.
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::Error,
+    process::Command,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    stats::Stats,
+    timings::{Timing, Timings},
+    Puzzle,
+};
+
+static HISTORY_FILE_PATH: &str = "./data/timings_history.json";
+
+/// A single stored `cargo time` run.
+#[derive(Clone, Debug)]
+pub struct Run {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    /// Short git revision the run was benched at, suffixed with `-dirty` if the tree had changes.
+    pub revision: Option<String>,
+    /// Label of the machine the run was benched on.
+    pub machine: String,
+    pub timings: Vec<Timing>,
+}
+
+/// Represents all stored runs, oldest first.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub data: Vec<Run>,
+}
+
+/// The median of a part compared to its baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub puzzle: Puzzle,
+    pub part: u8,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Revision of the run that `baseline` was taken from.
+    pub baseline_revision: Option<String>,
+}
+
+impl Run {
+    /// Creates a run of `timings` for the current time, git revision and machine.
+    pub fn new(timings: &Timings) -> Self {
+        Run {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            revision: git_revision(),
+            machine: machine_label(),
+            timings: timings.data.clone(),
+        }
+    }
+}
+
+impl History {
+    /// Dehydrate history to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(HISTORY_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate history from a JSON file. If not present, returns an empty history.
+    pub fn read_from_file() -> Self {
+        let Ok(s) = fs::read_to_string(HISTORY_FILE_PATH) else {
+            return History::default();
+        };
+
+        match History::try_from(s) {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("{e}");
+                History::default()
+            }
+        }
+    }
+
+    /// Returns the most recent stats of a part benched on `machine`, and the run they belong to.
+    pub fn baseline(&self, machine: &str, puzzle: Puzzle, part: u8) -> Option<(&Run, Stats)> {
+        self.data
+            .iter()
+            .rev()
+            .filter(|run| run.machine == machine)
+            .find_map(|run| {
+                let timing = run.timings.iter().find(|t| t.puzzle == puzzle)?;
+                part_stats(timing, part).map(|stats| (run, stats))
+            })
+    }
+
+    /// Compares every benched part of `timings` to its baseline on `machine`.
+    /// Parts without a baseline are skipped.
+    pub fn compare(&self, machine: &str, timings: &Timings) -> Vec<Comparison> {
+        timings
+            .data
+            .iter()
+            .flat_map(|timing| [1, 2].map(|part| (timing, part)))
+            .filter_map(|(timing, part)| {
+                let current = part_stats(timing, part)?;
+                let (run, baseline) = self.baseline(machine, timing.puzzle, part)?;
+                Some(Comparison {
+                    puzzle: timing.puzzle,
+                    part,
+                    baseline: baseline.median,
+                    current: current.median,
+                    baseline_revision: run.revision.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Comparison {
+    /// Relative change of the median in percent. Positive values mean the part got slower.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn change_percent(&self) -> f64 {
+        let baseline = self.baseline.as_nanos().max(1) as f64;
+        (self.current.as_nanos() as f64 - baseline) / baseline * 100.0
+    }
+
+    /// Returns `true` if the part got slower by more than `threshold` percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change_percent() > threshold
+    }
+}
+
+fn part_stats(timing: &Timing, part: u8) -> Option<Stats> {
+    match part {
+        1 => timing.part_1_stats,
+        2 => timing.part_2_stats,
+        _ => None,
+    }
+}
+
+fn git_revision() -> Option<String> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let revision = git(&["rev-parse", "--short", "HEAD"])?;
+    let is_dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|status| !status.is_empty());
+
+    Some(if is_dirty {
+        format!("{revision}-dirty")
+    } else {
+        revision
+    })
+}
+
+/// Reads the machine label from `AOC_MACHINE`, falling back to the hostname.
+fn machine_label() -> String {
+    ["AOC_MACHINE", "HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|key| env::var(key).ok())
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<History> for JsonValue {
+    fn from(value: History) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for History {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(History {
+            data: json_data
+                .iter()
+                .map(Run::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Run> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Run) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert(
+            "revision".into(),
+            value
+                .revision
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert("machine".into(), JsonValue::String(value.machine.clone()));
+        map.insert(
+            "timings".into(),
+            JsonValue::Array(value.timings.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Run {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected run to be a JSON object.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u64)
+            .ok_or("Expected run.timestamp to be a number.")?;
+
+        let revision = json
+            .get("revision")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected run.revision to be null or string.")?;
+
+        let machine = json
+            .get("machine")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected run.machine to be a string.")?;
+
+        let timings = json
+            .get("timings")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("Expected run.timings to be an array.")?
+            .iter()
+            .map(Timing::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Run {
+            timestamp,
+            revision: revision.cloned(),
+            machine: machine.clone(),
+            timings,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{History, Run};
+    use crate::{
+        day,
+        template::{
+            stats::Stats,
+            timings::{Timing, Timings},
+            Day,
+        },
+    };
+    use std::time::Duration;
+
+    fn timing(day: Day, part_1_micros: u64, part_2_micros: Option<u64>) -> Timing {
+        let stats = |micros| Stats::single(Duration::from_micros(micros));
+        Timing {
+            part_1_stats: Some(stats(part_1_micros)),
+            part_2_stats: part_2_micros.map(stats),
+            ..Timing::new(day.into())
+        }
+    }
+
+    fn run(revision: &str, machine: &str, timings: Vec<Timing>) -> Run {
+        Run {
+            timestamp: 1_700_000_000,
+            revision: Some(revision.into()),
+            machine: machine.into(),
+            timings,
+        }
+    }
+
+    fn get_mock_history() -> History {
+        History {
+            data: vec![
+                run("aaa", "laptop", vec![timing(day!(1), 100, Some(200))]),
+                run("bbb", "desktop", vec![timing(day!(1), 50, Some(50))]),
+                run("ccc", "laptop", vec![timing(day!(2), 10, None)]),
+            ],
+        }
+    }
+
+    #[test]
+    fn finds_latest_baseline_of_machine() {
+        let history = get_mock_history();
+
+        let (run, stats) = history.baseline("laptop", day!(1).into(), 1).unwrap();
+        assert_eq!(run.revision.as_deref(), Some("aaa"));
+        assert_eq!(stats.median, Duration::from_micros(100));
+
+        let (run, _) = history.baseline("laptop", day!(2).into(), 1).unwrap();
+        assert_eq!(run.revision.as_deref(), Some("ccc"));
+
+        assert!(history.baseline("laptop", day!(2).into(), 2).is_none());
+        assert!(history.baseline("server", day!(1).into(), 1).is_none());
+    }
+
+    #[test]
+    fn detects_regressions() {
+        let history = get_mock_history();
+        let current = Timings {
+            data: vec![timing(day!(1), 109, Some(300)), timing(day!(3), 1, Some(1))],
+        };
+
+        let comparisons = history.compare("laptop", &current);
+        assert_eq!(comparisons.len(), 2);
+
+        assert_eq!(comparisons[0].part, 1);
+        assert!((comparisons[0].change_percent() - 9.0).abs() < 1e-9);
+        assert!(!comparisons[0].is_regression(10.0));
+
+        assert_eq!(comparisons[1].part, 2);
+        assert!((comparisons[1].change_percent() - 50.0).abs() < 1e-9);
+        assert!(comparisons[1].is_regression(10.0));
+        assert!(!comparisons[1].is_regression(50.0));
+    }
+
+    #[test]
+    fn roundtrips_history() {
+        let history = get_mock_history();
+        let json = tinyjson::JsonValue::from(history.clone())
+            .stringify()
+            .unwrap();
+        let parsed = History::try_from(json).unwrap();
+
+        assert_eq!(parsed.data.len(), 3);
+        assert_eq!(parsed.data[1].machine, "desktop");
+        assert_eq!(parsed.data[1].revision.as_deref(), Some("bbb"));
+        assert_eq!(parsed.data[1].timestamp, 1_700_000_000);
+        assert_eq!(
+            parsed.data[0].timings[0].part_2_stats,
+            history.data[0].timings[0].part_2_stats
+        );
+    }
+}
@@ -0,0 +1,84 @@
+/// Typed puzzle input.
+use std::{convert::Infallible, error::Error, str::FromStr};
+
+use crate::template::{LineContext, LineError};
+
+/// Parses a day's input into a typed value, once for all parts.
+///
+/// Pass `<Type>::from_input` as the `parser` of the [`solution!`](crate::solution) macro to have
+/// the parts receive a reference to the parsed value instead of the raw input.
+/// If parsing fails, the error is reported and the parts are not run.
+pub trait FromInput: Sized {
+    /// The error returned for malformed input, use [`Infallible`] if parsing can't fail.
+    type Error: Error + 'static;
+
+    fn from_input(input: &str) -> Result<Self, Self::Error>;
+}
+
+impl FromInput for String {
+    type Error = Infallible;
+
+    fn from_input(input: &str) -> Result<Self, Self::Error> {
+        Ok(input.to_string())
+    }
+}
+
+/// Parses every non-empty line with [`FromStr`].
+impl<T: FromStr> FromInput for Vec<T>
+where
+    T::Err: Error + Send + Sync + 'static,
+{
+    type Error = LineError;
+
+    fn from_input(input: &str) -> Result<Self, Self::Error> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| line.trim_end().parse().line_context(i, line))
+            .collect()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::FromInput;
+    use crate::template::{LineContext, LineError};
+
+    #[derive(Debug)]
+    struct Calories(Vec<u32>);
+
+    impl FromInput for Calories {
+        type Error = LineError;
+
+        fn from_input(input: &str) -> Result<Self, Self::Error> {
+            input
+                .lines()
+                .enumerate()
+                .map(|(i, line)| line.parse().line_context(i, line))
+                .collect::<Result<_, _>>()
+                .map(Calories)
+        }
+    }
+
+    #[test]
+    fn parses_custom_types() {
+        assert_eq!(Calories::from_input("1\n2\n").unwrap().0, vec![1, 2]);
+        assert_eq!(Calories::from_input("1\nx\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn parses_lines() {
+        let input = "1\n-2\r\n\n3\n";
+        assert_eq!(Vec::<i32>::from_input(input).unwrap(), vec![1, -2, 3]);
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let error = Vec::<u32>::from_input("1\nfoo\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.content, "foo");
+    }
+}
@@ -0,0 +1,161 @@
+/// Heap usage of solution parts, captured with DHAT.
+use std::collections::HashMap;
+use tinyjson::JsonValue;
+
+/// Heap usage of a single run of a solution part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Memory {
+    /// Total number of bytes allocated.
+    pub total_bytes: u64,
+    /// Total number of allocations.
+    pub total_blocks: u64,
+    /// Peak heap size in bytes.
+    pub max_bytes: u64,
+}
+
+impl Memory {
+    /// Formats the heap usage as an annotation for a printed result.
+    pub fn format(&self) -> String {
+        format!(
+            " [{} peak, {} in {} allocs]",
+            format_bytes(self.max_bytes),
+            format_bytes(self.total_bytes),
+            self.total_blocks
+        )
+    }
+}
+
+/// Runs `func`, capturing its heap usage if the `dhat-heap` feature is enabled.
+/// The DHAT report of the run is written to `dhat-heap.json`.
+pub(crate) fn profile<T>(func: impl FnOnce() -> T) -> (T, Option<Memory>) {
+    #[cfg(feature = "dhat-heap")]
+    {
+        let _profiler = dhat::Profiler::new_heap();
+        let result = func();
+        let stats = dhat::HeapStats::get();
+
+        let memory = Memory {
+            total_bytes: stats.total_bytes,
+            total_blocks: stats.total_blocks,
+            max_bytes: stats.max_bytes as u64,
+        };
+
+        (result, Some(memory))
+    }
+
+    #[cfg(not(feature = "dhat-heap"))]
+    {
+        (func(), None)
+    }
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 KiB`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Memory> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Memory) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "total_bytes".into(),
+            JsonValue::Number(value.total_bytes as f64),
+        );
+        map.insert(
+            "total_blocks".into(),
+            JsonValue::Number(value.total_blocks as f64),
+        );
+        map.insert(
+            "max_bytes".into(),
+            JsonValue::Number(value.max_bytes as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Memory {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory to be a JSON object.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let get = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        Ok(Memory {
+            total_bytes: get("total_bytes")?,
+            total_blocks: get("total_blocks")?,
+            max_bytes: get("max_bytes")?,
+        })
+    }
+}
+
+/// Parses a memory object that might be missing or null, e.g. in files written without DHAT.
+pub(crate) fn parse_optional_memory(value: Option<&JsonValue>) -> Result<Option<Memory>, String> {
+    match value {
+        Some(v) if !v.is_null() => Memory::try_from(v).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_bytes, Memory};
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn formats_memory() {
+        let memory = Memory {
+            total_bytes: 276,
+            total_blocks: 3,
+            max_bytes: 232,
+        };
+        assert_eq!(memory.format(), " [232 B peak, 276 B in 3 allocs]");
+    }
+
+    #[test]
+    fn roundtrips_memory() {
+        let memory = Memory {
+            total_bytes: 1 << 40,
+            total_blocks: 12,
+            max_bytes: 4096,
+        };
+        let json = tinyjson::JsonValue::from(&memory);
+        assert_eq!(Memory::try_from(&json).unwrap(), memory);
+    }
+}
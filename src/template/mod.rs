@@ -1,14 +1,31 @@
-use std::{env, fs};
+use std::{env, fs, io};
 
+pub mod answers;
 pub mod aoc_cli;
+pub mod aoc_client;
 pub mod commands;
+pub mod registry;
 pub mod runner;
 
 pub use day::*;
+pub use failure::{Failure, FailureKind, LineContext, LineError};
+pub use input::FromInput;
+pub use memory::Memory;
+pub use output::{OutputFormat, Record, PARSE_PART};
+pub use puzzle::*;
+pub use stats::Stats;
 
 mod day;
+mod failure;
+mod history;
+mod input;
+mod memory;
+mod output;
+mod puzzle;
 mod readme_benchmarks;
 mod run_multi;
+mod stats;
+mod submissions;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
@@ -17,52 +34,109 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 
 /// Helper function that reads a text file to a string.
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+pub fn read_file(folder: &str, puzzle: impl Into<Puzzle>) -> String {
+    try_read_file(folder, puzzle).expect("could not open input file")
+}
+
+/// Helper function that reads a text file to a string, returning an error if it can't be read.
+pub fn try_read_file(folder: &str, puzzle: impl Into<Puzzle>) -> io::Result<String> {
+    let cwd = env::current_dir()?;
+    let filepath = cwd
+        .join("data")
+        .join(folder)
+        .join(format!("{}.txt", puzzle.into().name()));
+    fs::read_to_string(filepath)
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, puzzle: impl Into<Puzzle>, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
         .join("data")
         .join(folder)
-        .join(format!("{day}-{part}.txt"));
+        .join(format!("{}-{part}.txt", puzzle.into().name()));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Creates the constants `DAY` and `PUZZLE` and sets up the input and runner for each part.
+/// Also creates a `register` function that adds the day to an in-process [`registry::Registry`].
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// Solutions for a year other than [`DEFAULT_YEAR`] pass it as a trailing `year = <year>` parameter.
+///
+/// Parts return either `Option<T>` or `Result<T, E>`, see [`runner::PartOutput`].
+///
+/// Solutions can parse their input once with a trailing `parser = <fn(&str) -> Result<T, E>>` parameter, e.g. `parser = Network::from_input`
+/// for a type implementing [`FromInput`]. The parts then receive a `&T` and the parse time is reported as its own row.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+    ($day:expr $(, year = $year:expr)? $(, parser = $parser:expr)?) => {
+        $crate::solution!(@impl $day, [$($year)?], [$($parser)?], [part_one, 1] [part_two, 2]);
     };
-    ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+    ($day:expr, 1 $(, year = $year:expr)? $(, parser = $parser:expr)?) => {
+        $crate::solution!(@impl $day, [$($year)?], [$($parser)?], [part_one, 1]);
     };
-    ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+    ($day:expr, 2 $(, year = $year:expr)? $(, parser = $parser:expr)?) => {
+        $crate::solution!(@impl $day, [$($year)?], [$($parser)?], [part_two, 2]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@year) => {
+        $crate::template::DEFAULT_YEAR
+    };
+    (@year $year:expr) => {
+        $year
+    };
+
+    (@run [], $input:ident, $answers:ident, $( [$func:expr, $part:expr] )*) => {
+        $( $crate::template::runner::run_part($func, &$input, PUZZLE, $part, &$answers); )*
+    };
+    (@run [$parser:expr], $input:ident, $answers:ident, $( [$func:expr, $part:expr] )*) => {
+        if let Some(parsed) = $crate::template::runner::run_parse($parser, &$input, PUZZLE) {
+            $( $crate::template::runner::run_part($func, &parsed, PUZZLE, $part, &$answers); )*
+        }
+    };
+
+    (@register [], $registry:ident, $( [$func:expr, $part:expr] )*) => {
+        $registry.register(PUZZLE, &[$($part),*], |input: &str, is_timed: bool, answers: &$crate::template::answers::Answers, on_record: &mut dyn FnMut($crate::template::Record)| {
+            $( on_record($crate::template::runner::run_part_in_process($func, input, PUZZLE, $part, is_timed, answers)); )*
+        });
+    };
+    (@register [$parser:expr], $registry:ident, $( [$func:expr, $part:expr] )*) => {
+        $registry.register(PUZZLE, &[$crate::template::PARSE_PART, $($part),*], |input: &str, is_timed: bool, answers: &$crate::template::answers::Answers, on_record: &mut dyn FnMut($crate::template::Record)| {
+            let (parsed, record) = $crate::template::runner::run_parse_in_process($parser, input, PUZZLE, is_timed);
+            on_record(record);
+            // every part receives the same parsed input, the parts are skipped if parsing failed.
+            if let Some(parsed) = parsed {
+                $( on_record($crate::template::runner::run_part_in_process($func, &parsed, PUZZLE, $part, is_timed, answers)); )*
+            }
+        });
+    };
+
+    (@impl $day:expr, [$($year:expr)?], [$($parser:expr)?], $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
+        /// The current puzzle.
+        const PUZZLE: $crate::template::Puzzle =
+            $crate::template::Puzzle::new($crate::solution!(@year $($year)?), DAY);
+
         #[cfg(feature = "dhat-heap")]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[allow(dead_code)]
         fn main() {
-            use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::read_file("inputs", PUZZLE);
+            let answers = $crate::template::answers::Answers::read_from_file();
+            $crate::solution!(@run [$($parser)?], input, answers, $( [$func, $part] )*);
+        }
+
+        /// Adds this day to an in-process solution registry.
+        #[allow(dead_code)]
+        pub fn register(registry: &mut $crate::template::registry::Registry) {
+            $crate::solution!(@register [$($parser)?], registry, $( [$func, $part] )*);
         }
     };
 }
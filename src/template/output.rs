@@ -0,0 +1,306 @@
+/// Machine-readable output of solution runs.
+use std::{collections::HashMap, error::Error, fmt::Display, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    answers::Verdict,
+    failure::{parse_optional_failure, Failure},
+    memory::{parse_optional_memory, Memory},
+    stats::Stats,
+    Puzzle,
+};
+
+/// Controls how results are printed by `solve`, `all` and `time`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text with ANSI styling.
+    #[default]
+    Text,
+    /// One JSON record per solved part, emitted on its own line.
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns the format requested via `--format` in the current process arguments.
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        args.iter()
+            .position(|x| x == "--format")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Text => f.write_str("text"),
+            OutputFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = OutputFormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(OutputFormatFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing an [`OutputFormat`].
+#[derive(Debug)]
+pub struct OutputFormatFromStrError;
+
+impl Error for OutputFormatFromStrError {}
+
+impl Display for OutputFormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting an output format of either `text` or `json`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Part number of the records that measure parsing the input.
+pub const PARSE_PART: u8 = 0;
+
+/// The outcome of running a single solution part, or of parsing the input of a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub puzzle: Puzzle,
+    /// The part, or [`PARSE_PART`] if the record measures parsing the input.
+    pub part: u8,
+    pub answer: Option<String>,
+    pub stats: Stats,
+    /// Heap usage, if the solution was built with the `dhat-heap` feature.
+    pub memory: Option<Memory>,
+    /// The error of a part or parser that failed.
+    pub failure: Option<Failure>,
+    pub verdict: Verdict,
+}
+
+impl Record {
+    /// Returns `true` if the record measures parsing the input instead of a part.
+    pub fn is_parse(&self) -> bool {
+        self.part == PARSE_PART
+    }
+
+    /// Serializes the record to a single line of JSON.
+    pub fn to_json_line(&self) -> String {
+        // NOTE: `JsonValue::stringify` does not emit newlines for multi-line answers.
+        JsonValue::from(self).stringify().unwrap()
+    }
+}
+
+impl From<&Record> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Record) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        value.puzzle.insert_json(&mut map);
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert(
+            "answer".into(),
+            value
+                .answer
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert(
+            "duration_nanos".into(),
+            JsonValue::Number(value.stats.median.as_nanos() as f64),
+        );
+        map.insert(
+            "samples".into(),
+            JsonValue::Number(value.stats.samples as f64),
+        );
+        map.insert("stats".into(), JsonValue::from(&value.stats));
+        map.insert(
+            "memory".into(),
+            value
+                .memory
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+        map.insert(
+            "error".into(),
+            value
+                .failure
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+        map.insert(
+            "verdict".into(),
+            JsonValue::String(value.verdict.to_string()),
+        );
+
+        if let Verdict::Wrong(expected) = &value.verdict {
+            map.insert("expected".into(), JsonValue::String(expected.clone()));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Record {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected record to be a JSON object.")?;
+
+        let puzzle = Puzzle::from_json(json)
+            .ok_or("Expected record.day to be a Day struct and record.year a number.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u8)
+            .ok_or("Expected record.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected record.answer to be null or string.")?;
+
+        let stats = json
+            .get("stats")
+            .ok_or("Expected record.stats to be a stats object.")
+            .map(Stats::try_from)??;
+
+        let memory = parse_optional_memory(json.get("memory"))
+            .or(Err("Expected record.memory to be null or a memory object."))?;
+
+        let failure = parse_optional_failure(json.get("error"))
+            .or(Err("Expected record.error to be null or an error object."))?;
+
+        let expected = json.get("expected").and_then(|v| v.get::<String>());
+
+        let verdict = match json.get("verdict").and_then(|v| v.get::<String>()) {
+            Some(v) if v == "correct" => Verdict::Correct,
+            Some(v) if v == "wrong" => Verdict::Wrong(
+                expected
+                    .cloned()
+                    .ok_or("Expected record.expected to be a string.")?,
+            ),
+            Some(v) if v == "unknown" => Verdict::Unknown,
+            _ => return Err("Expected record.verdict to be a verdict.".into()),
+        };
+
+        Ok(Record {
+            puzzle,
+            part,
+            answer: answer.cloned(),
+            stats,
+            memory,
+            failure,
+            verdict,
+        })
+    }
+}
+
+impl FromStr for Record {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let json = JsonValue::from_str(s).or(Err("not valid JSON."))?;
+        Record::try_from(&json)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{OutputFormat, Record};
+    use crate::{
+        day,
+        template::{
+            answers::Verdict, stats::Stats, Failure, FailureKind, Memory, Puzzle, PARSE_PART,
+        },
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn parses_output_format() {
+        assert_eq!("text".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
+        assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn roundtrips_records() {
+        let record = Record {
+            puzzle: Puzzle::new(2021, day!(10)),
+            part: 2,
+            answer: Some("##..\n..##".into()),
+            stats: Stats::single(Duration::from_micros(42)),
+            memory: Some(Memory {
+                total_bytes: 276,
+                total_blocks: 3,
+                max_bytes: 232,
+            }),
+            failure: None,
+            verdict: Verdict::Wrong("#..#\n.##.".into()),
+        };
+        let line = record.to_json_line();
+        assert!(!line.contains('\n'));
+        assert_eq!(line.parse::<Record>().unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_missing_answers() {
+        let record = Record {
+            puzzle: Puzzle::from(day!(1)),
+            part: 1,
+            answer: None,
+            stats: Stats::single(Duration::from_nanos(10)),
+            memory: None,
+            failure: None,
+            verdict: Verdict::Unknown,
+        };
+        assert_eq!(record.to_json_line().parse::<Record>().unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_failures() {
+        let record = Record {
+            puzzle: Puzzle::from(day!(16)),
+            part: PARSE_PART,
+            answer: None,
+            stats: Stats::single(Duration::from_nanos(10)),
+            memory: None,
+            failure: Some(Failure {
+                kind: FailureKind::Error,
+                chain: vec!["invalid input on line 2".into(), "invalid digit".into()],
+                line: Some((2, "Valve BB has flow rate=x".into())),
+            }),
+            verdict: Verdict::Unknown,
+        };
+        assert_eq!(record.to_json_line().parse::<Record>().unwrap(), record);
+    }
+
+    #[test]
+    fn reads_records_without_year() {
+        let line = r#"{"day":"01","part":1,"answer":"1","stats":{"samples":1,"outliers":0,"median_nanos":1,"min_nanos":1,"max_nanos":1,"std_dev_nanos":0,"p95_nanos":1},"verdict":"unknown"}"#;
+        assert_eq!(
+            line.parse::<Record>().unwrap().puzzle,
+            Puzzle::from(day!(1))
+        );
+    }
+
+    #[test]
+    fn rejects_text_lines() {
+        assert!("Part 1: 0 (74.13ns @ 100000 samples)"
+            .parse::<Record>()
+            .is_err());
+    }
+}
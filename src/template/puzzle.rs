@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+#[cfg(feature = "today")]
+use chrono::{Datelike, Local};
+
+/// The year that `AOC_YEAR` was set to at compile time.
+///
+/// Solutions of this year live in `src/bin/<day>.rs` and read `data/<folder>/<day>.txt`.
+/// Solutions of any other year are prefixed with their year, e.g. `src/bin/2021-01.rs`.
+pub const DEFAULT_YEAR: u16 = match option_env!("AOC_YEAR") {
+    Some(year) => parse_year(year),
+    None => panic!("AOC_YEAR is not set. Set it in the `[env]` section of `.cargo/config.toml`."),
+};
+
+const fn parse_year(s: &str) -> u16 {
+    let bytes = s.as_bytes();
+    assert!(bytes.len() == 4, "AOC_YEAR must be a four digit year.");
+
+    let mut year = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(
+            bytes[i].is_ascii_digit(),
+            "AOC_YEAR must be a four digit year."
+        );
+        year = year * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+    year
+}
+
+/// Identifies a puzzle by its event year and day.
+///
+/// # Display
+/// This value displays as `<year>-<day>`, and can be parsed from the same format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: Day,
+}
+
+impl Puzzle {
+    pub const fn new(year: u16, day: Day) -> Self {
+        Self { year, day }
+    }
+
+    /// Returns `true` if this puzzle belongs to [`DEFAULT_YEAR`].
+    pub fn is_default_year(&self) -> bool {
+        self.year == DEFAULT_YEAR
+    }
+
+    /// Name of the solution binary and data files of this puzzle, e.g. `01` or `2021-01`.
+    pub fn name(&self) -> String {
+        if self.is_default_year() {
+            self.day.to_string()
+        } else {
+            self.to_string()
+        }
+    }
+
+    /// Human-readable title, e.g. `Day 01` or `2021 Day 01`.
+    pub fn title(&self) -> String {
+        if self.is_default_year() {
+            format!("Day {}", self.day)
+        } else {
+            format!("{} Day {}", self.year, self.day)
+        }
+    }
+
+    /// Returns every puzzle of `year`, from the 1st to the 25th.
+    pub fn all_of_year(year: u16) -> impl Iterator<Item = Self> {
+        super::all_days().map(move |day| Self::new(year, day))
+    }
+
+    /// Reads the `year` and `day` keys of a JSON object.
+    /// Files written before multi-year support don't have a `year`, these refer to [`DEFAULT_YEAR`].
+    pub(crate) fn from_json(json: &HashMap<String, JsonValue>) -> Option<Self> {
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let year = match json.get("year") {
+            Some(year) => *year.get::<f64>()? as u16,
+            None => DEFAULT_YEAR,
+        };
+
+        Some(Self::new(year, day))
+    }
+
+    /// Writes the `year` and `day` keys to a JSON object.
+    pub(crate) fn insert_json(&self, map: &mut HashMap<String, JsonValue>) {
+        map.insert("day".into(), JsonValue::String(self.day.to_string()));
+        map.insert("year".into(), JsonValue::Number(f64::from(self.year)));
+    }
+}
+
+#[cfg(feature = "today")]
+impl Puzzle {
+    /// Returns today's puzzle if it's between the 1st and the 25th of december, `None` otherwise.
+    pub fn today() -> Option<Self> {
+        let year = u16::try_from(Local::now().year()).ok()?;
+        Some(Self::new(year, Day::today()?))
+    }
+}
+
+impl From<Day> for Puzzle {
+    /// Creates the puzzle of `day` in [`DEFAULT_YEAR`].
+    fn from(day: Day) -> Self {
+        Self::new(DEFAULT_YEAR, day)
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.year, self.day)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Puzzle {
+    type Err = PuzzleFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, day) = s.split_once('-').ok_or(PuzzleFromStrError)?;
+        let year = year.parse().map_err(|_| PuzzleFromStrError)?;
+        let day = day.parse().map_err(|_| PuzzleFromStrError)?;
+        Ok(Self::new(year, day))
+    }
+}
+
+/// An error which can be returned when parsing a [`Puzzle`].
+#[derive(Debug)]
+pub struct PuzzleFromStrError;
+
+impl Error for PuzzleFromStrError {}
+
+impl Display for PuzzleFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a puzzle in the format `<year>-<day>`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Puzzle, DEFAULT_YEAR};
+    use crate::day;
+
+    #[test]
+    fn names_puzzles() {
+        let default = Puzzle::from(day!(1));
+        assert_eq!(default.year, DEFAULT_YEAR);
+        assert_eq!(default.name(), "01");
+        assert_eq!(default.title(), "Day 01");
+
+        let other = Puzzle::new(DEFAULT_YEAR - 1, day!(12));
+        assert_eq!(other.name(), format!("{}-12", DEFAULT_YEAR - 1));
+        assert_eq!(other.title(), format!("{} Day 12", DEFAULT_YEAR - 1));
+    }
+
+    #[test]
+    fn displays_puzzles() {
+        assert_eq!(Puzzle::new(2021, day!(8)).to_string(), "2021-08");
+    }
+
+    #[test]
+    fn parses_puzzles() {
+        assert_eq!(
+            "2021-08".parse::<Puzzle>().unwrap(),
+            Puzzle::new(2021, day!(8))
+        );
+        assert!("2021-26".parse::<Puzzle>().is_err());
+        assert!("08".parse::<Puzzle>().is_err());
+    }
+
+    #[test]
+    fn orders_puzzles_by_year_first() {
+        let mut puzzles = vec![
+            Puzzle::new(2022, day!(1)),
+            Puzzle::new(2021, day!(25)),
+            Puzzle::new(2021, day!(2)),
+        ];
+        puzzles.sort_unstable();
+        assert_eq!(
+            puzzles,
+            vec![
+                Puzzle::new(2021, day!(2)),
+                Puzzle::new(2021, day!(25)),
+                Puzzle::new(2022, day!(1)),
+            ]
+        );
+    }
+}
@@ -1,9 +1,10 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{fmt::Display, fs, io, time::Duration};
 
+use crate::template::memory::{format_bytes, Memory};
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::Puzzle;
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -19,14 +20,47 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(message) => write!(f, "{message}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
 pub struct TablePosition {
     pos_start: usize,
     pos_end: usize,
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(puzzle: Puzzle) -> String {
+    format!("./src/bin/{}.rs", puzzle.name())
+}
+
+/// Link text of a puzzle in the table, e.g. `Day 1` or `2021 Day 1`.
+fn get_label(puzzle: Puzzle) -> String {
+    let day = puzzle.day.into_inner();
+    if puzzle.is_default_year() {
+        format!("Day {day}")
+    } else {
+        format!("{} Day {day}", puzzle.year)
+    }
+}
+
+/// Memory cell of a part, e.g. `` `232 B` peak, 3 allocs ``.
+fn format_memory(memory: Option<Memory>) -> String {
+    memory.map_or_else(
+        || "-".into(),
+        |m| {
+            format!(
+                "`{}` peak, {} allocs",
+                format_bytes(m.max_bytes),
+                m.total_blocks
+            )
+        },
+    )
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
@@ -51,26 +85,59 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
+/// Duration cell of a part, e.g. `` `74.1ms` ``.
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or_else(|| "`-`".into(), |d| format!("`{d:.1?}`"))
+}
+
 fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
     let header = format!("{prefix} Benchmarks");
+    // parse and memory columns are only shown if a solution has a parser or a DHAT run has been stored.
+    let has_parse = timings.has_parse();
+    let has_memory = timings.has_memory();
+    let total = timings.total();
+
+    let mut columns = vec!["Day"];
+    if has_parse {
+        columns.push("Parse");
+    }
+    columns.extend(["Part 1", "Part 2", "Share"]);
+    if has_memory {
+        columns.extend(["Part 1 Memory", "Part 2 Memory"]);
+    }
 
     let mut lines: Vec<String> = vec![
         MARKER.into(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        format!("| {} |", columns.join(" | ")),
+        format!("|{}", " :---: |".repeat(columns.len())),
     ];
 
     for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+        let mut cells = vec![format!(
+            "[{}]({})",
+            get_label(timing.puzzle),
+            get_path_for_bin(timing.puzzle)
+        )];
+
+        if has_parse {
+            cells.push(format_duration(timing.parse));
+        }
+
+        cells.push(format_duration(timing.part_1));
+        cells.push(format_duration(timing.part_2));
+
+        #[allow(clippy::cast_precision_loss)]
+        let share = timing.total().as_nanos() as f64 / total.as_nanos().max(1) as f64 * 100.0;
+        cells.push(format!("{share:.1}%"));
+
+        if has_memory {
+            cells.push(format_memory(timing.part_1_memory));
+            cells.push(format_memory(timing.part_2_memory));
+        }
+
+        lines.push(format!("| {} |", cells.join(" | ")));
     }
 
     lines.push(String::new());
@@ -99,28 +166,34 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day,
+        template::timings::{Timing, Timings},
+        template::{Memory, Puzzle},
+    };
+    use std::time::Duration;
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    part_1: Some(Duration::from_millis(5)),
+                    ..Timing::new(Puzzle::new(2021, day!(25)))
                 },
                 Timing {
-                    day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    part_1: Some(Duration::from_millis(10)),
+                    part_2: Some(Duration::from_millis(20)),
+                    ..Timing::new(day!(1).into())
                 },
                 Timing {
-                    day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
-                    total_nanos: 9e+10,
+                    part_1: Some(Duration::from_millis(30)),
+                    part_2: Some(Duration::from_millis(40)),
+                    ..Timing::new(day!(2).into())
+                },
+                Timing {
+                    part_1: Some(Duration::from_millis(40)),
+                    part_2: Some(Duration::from_millis(50)),
+                    ..Timing::new(day!(4).into())
                 },
             ],
         }
@@ -166,11 +239,12 @@ mod tests {
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
-            "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| Day | Part 1 | Part 2 | Share |",
+            "| :---: | :---: | :---: | :---: |",
+            "| [2021 Day 25](./src/bin/2021-25.rs) | `5.0ms` | `-` | 2.6% |",
+            "| [Day 1](./src/bin/01.rs) | `10.0ms` | `20.0ms` | 15.4% |",
+            "| [Day 2](./src/bin/02.rs) | `30.0ms` | `40.0ms` | 35.9% |",
+            "| [Day 4](./src/bin/04.rs) | `40.0ms` | `50.0ms` | 46.2% |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -179,4 +253,44 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_memory() {
+        let mut timings = get_mock_timings();
+        timings.data[1].part_1_memory = Some(Memory {
+            total_bytes: 276,
+            total_blocks: 3,
+            max_bytes: 232,
+        });
+        timings.data[1].part_2_memory = Some(Memory {
+            total_bytes: 4096,
+            total_blocks: 1,
+            max_bytes: 2048,
+        });
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0).unwrap();
+
+        assert!(s.contains("| Day | Part 1 | Part 2 | Share | Part 1 Memory | Part 2 Memory |"));
+        assert!(s.contains(
+            "| [Day 1](./src/bin/01.rs) | `10.0ms` | `20.0ms` | 15.4% | `232 B` peak, 3 allocs | `2.0 KiB` peak, 1 allocs |"
+        ));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30.0ms` | `40.0ms` | 35.9% | - | - |"));
+    }
+
+    #[test]
+    fn format_benchmarks_with_parse() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = Some(Duration::from_micros(500));
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 195.5).unwrap();
+
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 | Share |"));
+        assert!(s.contains("| :---: | :---: | :---: | :---: | :---: |"));
+        assert!(
+            s.contains("| [Day 1](./src/bin/01.rs) | `500.0µs` | `10.0ms` | `20.0ms` | 15.6% |")
+        );
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `-` | `30.0ms` | `40.0ms` | 35.8% |"));
+    }
 }
@@ -0,0 +1,219 @@
+/// In-process registry of solutions.
+/// The `solution!` macro generates a `register` function for every day, which the main binary calls for all days compiled into it.
+use std::collections::BTreeMap;
+
+use crate::template::{answers::Answers, Puzzle, Record};
+
+/// Type-erased runner for all parts of a day.
+/// Receives the puzzle input, whether the parts should be benched and the known-good answers to check them against.
+/// Passes the record of parsing the input, if the day has a parser, and of every part to the callback, in part order.
+/// Days with a parser parse the input once and skip the parts if parsing fails.
+pub type DayRunner = fn(&str, bool, &Answers, &mut dyn FnMut(Record));
+
+/// Solutions that can be run in the current process, keyed by puzzle.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    puzzles: BTreeMap<Puzzle, (&'static [u8], DayRunner)>,
+}
+
+impl Registry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the runner of a puzzle, replacing any runner previously registered for it.
+    /// `parts` are the parts the runner reports, in order, starting with [`PARSE_PART`](crate::template::PARSE_PART)
+    /// if the day has a parser.
+    pub fn register(&mut self, puzzle: Puzzle, parts: &'static [u8], runner: DayRunner) {
+        self.puzzles.insert(puzzle, (parts, runner));
+    }
+
+    /// Returns `true` if `puzzle` is registered.
+    pub fn contains(&self, puzzle: Puzzle) -> bool {
+        self.puzzles.contains_key(&puzzle)
+    }
+
+    /// Returns the runner of `puzzle`, if it is registered.
+    pub fn get(&self, puzzle: Puzzle) -> Option<DayRunner> {
+        self.puzzles.get(&puzzle).map(|&(_, runner)| runner)
+    }
+
+    /// Returns the parts registered for `puzzle`, in the order they are run.
+    pub fn parts(&self, puzzle: Puzzle) -> Option<&'static [u8]> {
+        self.puzzles.get(&puzzle).map(|&(parts, _)| parts)
+    }
+
+    /// Runs all parts of `puzzle` against `input` and returns their records, in part order.
+    /// Returns [`None`] if the puzzle is not registered.
+    pub fn run(
+        &self,
+        puzzle: Puzzle,
+        input: &str,
+        is_timed: bool,
+        answers: &Answers,
+    ) -> Option<Vec<Record>> {
+        let runner = self.get(puzzle)?;
+        let mut records = vec![];
+        runner(input, is_timed, answers, &mut |record| records.push(record));
+        Some(records)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Registry;
+    use crate::{
+        day,
+        template::{
+            answers::Answers, runner::run_part_in_process, FailureKind, LineContext, LineError,
+            Puzzle, Record, PARSE_PART,
+        },
+    };
+    use std::{cell::Cell, num::ParseIntError};
+
+    const PUZZLE: Puzzle = Puzzle::new(2021, day!(2));
+
+    fn part_one(input: &str) -> Option<usize> {
+        Some(input.len())
+    }
+
+    fn part_two(input: &str) -> Option<String> {
+        Some(input.to_uppercase())
+    }
+
+    fn register(registry: &mut Registry) {
+        crate::solution!(@register [], registry, [part_one, 1] [part_two, 2]);
+    }
+
+    fn run(registry: &Registry, input: &str) -> Vec<Record> {
+        registry
+            .run(PUZZLE, input, false, &Answers::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn runs_registered_parts_in_order() {
+        let mut registry = Registry::new();
+        register(&mut registry);
+
+        assert!(registry.contains(PUZZLE));
+        assert_eq!(registry.parts(PUZZLE), Some(&[1, 2][..]));
+        let records = run(&registry, "abc");
+        let answers: Vec<_> = records.iter().map(|r: &Record| r.answer.clone()).collect();
+        assert_eq!(answers, vec![Some("3".into()), Some("ABC".into())]);
+        assert_eq!(records[0].part, 1);
+        assert_eq!(records[1].part, 2);
+        assert_eq!(records[1].stats.samples, 1);
+    }
+
+    #[test]
+    fn handles_unregistered_puzzles() {
+        let mut registry = Registry::new();
+        register(&mut registry);
+
+        assert!(!registry.contains(Puzzle::new(2021, day!(3))));
+        assert!(!registry.contains(Puzzle::new(2022, day!(2))));
+        assert!(registry
+            .run(
+                Puzzle::new(2021, day!(3)),
+                "abc",
+                false,
+                &Answers::default()
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn replaces_registered_days() {
+        let mut registry = Registry::new();
+        register(&mut registry);
+        crate::solution!(@register [], registry, [part_two, 2]);
+
+        let records = run(&registry, "abc");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].part, 2);
+    }
+
+    thread_local! {
+        static PARSE_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn parse(input: &str) -> Result<Vec<u32>, LineError> {
+        PARSE_CALLS.set(PARSE_CALLS.get() + 1);
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| line.parse().line_context(i, line))
+            .collect()
+    }
+
+    fn sum(input: &[u32]) -> Result<u32, ParseIntError> {
+        Ok(input.iter().sum())
+    }
+
+    fn max(input: &[u32]) -> Option<u32> {
+        input.iter().max().copied()
+    }
+
+    fn register_parsed(registry: &mut Registry) {
+        crate::solution!(@register [parse], registry, [sum, 1] [max, 2]);
+    }
+
+    #[test]
+    fn runs_parsed_input_first() {
+        let mut registry = Registry::new();
+        register_parsed(&mut registry);
+
+        let records = run(&registry, "1\n2");
+        assert_eq!(records.len(), 3);
+        assert!(records[0].is_parse());
+        assert_eq!(records[0].answer, None);
+        assert_eq!(records[1].answer, Some("3".into()));
+        assert_eq!(records[1].failure, None);
+        assert_eq!(records[2].answer, Some("2".into()));
+    }
+
+    #[test]
+    fn parses_input_once() {
+        let mut registry = Registry::new();
+        register_parsed(&mut registry);
+
+        PARSE_CALLS.set(0);
+        let records = run(&registry, "5\n7\n3");
+        assert_eq!(registry.parts(PUZZLE), Some(&[PARSE_PART, 1, 2][..]));
+        assert_eq!(records.len(), 3);
+        assert_eq!(PARSE_CALLS.get(), 1);
+    }
+
+    #[test]
+    fn skips_parts_if_parsing_fails() {
+        let mut registry = Registry::new();
+        register_parsed(&mut registry);
+
+        let records = run(&registry, "1\nx");
+        assert_eq!(records.len(), 1);
+        let failure = records[0].failure.as_ref().unwrap();
+        assert_eq!(failure.line, Some((2, "x".into())));
+    }
+
+    #[test]
+    fn catches_panicking_parts() {
+        let mut registry = Registry::new();
+        registry.register(PUZZLE, &[1, 2], |input, is_timed, answers, on_record| {
+            let panicking = |_: &str| -> Option<u32> { panic!("oh no") };
+            on_record(run_part_in_process(
+                panicking, input, PUZZLE, 1, is_timed, answers,
+            ));
+            on_record(run_part_in_process(
+                part_two, input, PUZZLE, 2, is_timed, answers,
+            ));
+        });
+
+        let records = run(&registry, "abc");
+        let failure = records[0].failure.as_ref().unwrap();
+        assert_eq!(failure.kind, FailureKind::Panic);
+        assert!(failure.chain[0].starts_with("oh no"));
+        assert_eq!(records[1].answer, Some("ABC".into()));
+    }
+}
@@ -1,48 +1,317 @@
-use std::{collections::HashSet, io};
-
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{Display, Write},
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use super::{
-    all_days,
-    timings::{Timing, Timings},
+use crate::template::{
+    answers::Answers,
+    registry::Registry,
+    runner::{failed_record, format_parse, format_record_text},
+    stats::Stats,
+    try_read_file, Failure, FailureKind, OutputFormat, Puzzle, Record, ANSI_BOLD, ANSI_ITALIC,
+    ANSI_RESET,
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+use super::timings::{Timing, Timings};
+
+/// Options of a [`run_multi`] run.
+#[derive(Clone, Copy, Debug)]
+pub struct RunOptions {
+    /// Run optimized builds. Days compiled into the registry then run in the current process, which is built with
+    /// optimizations. All other days, and every day of a run without `is_release`, spawn their solution binary with cargo.
+    pub is_release: bool,
+    /// Bench every part. Timed runs always run one day at a time, so concurrent days don't skew the timings.
+    pub is_timed: bool,
+    /// Run every day's own binary built with the `dhat-heap` feature, so the heap usage of each part is captured.
+    pub is_dhat: bool,
+    pub format: OutputFormat,
+    /// Maximum number of days that run concurrently.
+    pub jobs: usize,
+    /// Time after which a day is reported as timed out and the run continues with the next day.
+    /// Timed runs with a timeout spawn every day's solution binary, so a day that timed out can be killed instead of
+    /// skewing the timings of the following days.
+    pub timeout: Option<Duration>,
+}
 
-    let mut need_space = false;
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            is_release: false,
+            is_timed: false,
+            is_dhat: false,
+            format: OutputFormat::Text,
+            jobs: 1,
+            timeout: None,
+        }
+    }
+}
+
+/// Runs the solutions for `puzzles_to_run`, see [`RunOptions`].
+///
+/// Output is buffered per day and printed in year and day order.
+/// Panics of parts are caught by the runners and reported as failures of the panicking part.
+pub fn run_multi(
+    registry: &Registry,
+    puzzles_to_run: &HashSet<Puzzle>,
+    options: RunOptions,
+) -> MultiRun {
+    let RunOptions {
+        is_release,
+        is_timed,
+        is_dhat,
+        format,
+        jobs,
+        timeout,
+    } = options;
+
+    let mut timings: Vec<Timing> = Vec::with_capacity(puzzles_to_run.len());
+    let mut records: Vec<Record> = vec![];
+
+    // NOTE: use non-duplicate, sorted puzzle values.
+    let mut puzzles: Vec<Puzzle> = puzzles_to_run.iter().copied().collect();
+    puzzles.sort_unstable();
+    let jobs = if is_timed || is_dhat {
+        1
+    } else {
+        jobs.clamp(1, puzzles.len().max(1))
+    };
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
+    // shared with the threads of days that run in process.
+    let answers = Arc::new(Answers::read_from_file());
+
+    // threads of timed out days can't be stopped, timed runs kill the process of the day instead.
+    let can_run_in_process = is_release && !is_dhat && !(is_timed && timeout.is_some());
+
+    let run_day = |puzzle: Puzzle| {
+        // days that are not registered, e.g. in dhat builds, are expected to have both parts.
+        let parts = registry.parts(puzzle).unwrap_or(&[1, 2]);
+        if can_run_in_process && registry.contains(puzzle) {
+            run_in_process(registry, &answers, puzzle, is_timed, format, timeout)
+        } else {
+            child_commands::run_solution(puzzle, parts, options)
+                .unwrap_or_else(|e| panic!("Failed to run {puzzle}: {e}"))
+        }
+    };
+
+    let mut print_day = |index: usize, puzzle: Puzzle, output: DayOutput| {
+        if format == OutputFormat::Text {
+            if index > 0 {
                 println!();
             }
-            need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+            println!("{ANSI_BOLD}{}{ANSI_RESET}", puzzle.title());
             println!("------");
+        }
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        eprint!("{}", output.stderr);
+        print!("{}", output.stdout);
 
-            if output.is_empty() {
+        if output.records.is_empty() {
+            if format == OutputFormat::Text {
                 println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+            }
+        } else {
+            let val = child_commands::collect_timing(&output.records, puzzle);
+            timings.push(val);
+            records.extend(output.records);
+        }
+    };
+
+    if jobs == 1 {
+        for (index, puzzle) in puzzles.iter().enumerate() {
+            print_day(index, *puzzle, run_day(*puzzle));
+        }
+    } else {
+        let next_index = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..jobs {
+                let sender = sender.clone();
+                let (puzzles, next_index, run_day) = (&puzzles, &next_index, &run_day);
+
+                scope.spawn(move || loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(puzzle) = puzzles.get(index) else {
+                        break;
+                    };
+                    sender.send((index, run_day(*puzzle))).unwrap();
+                });
+            }
+            drop(sender);
+
+            // days finish in any order, print them as soon as all preceding days are printed.
+            let mut pending: BTreeMap<usize, DayOutput> = BTreeMap::new();
+            let mut next_to_print = 0;
+
+            for (index, output) in receiver {
+                pending.insert(index, output);
+                while let Some(output) = pending.remove(&next_to_print) {
+                    print_day(next_to_print, puzzles[next_to_print], output);
+                    next_to_print += 1;
+                }
             }
         });
+    }
+
+    if format == OutputFormat::Text {
+        print_failures(&records);
+    }
 
-    if is_timed {
+    let timings = if is_timed || is_dhat {
         let timings = Timings { data: timings };
-        let total_millis = timings.total_millis();
-        println!(
-            "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
-        );
+        if is_timed && format == OutputFormat::Text {
+            let total_millis = timings.total_millis();
+            println!(
+                "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            );
+        }
         Some(timings)
     } else {
         None
+    };
+
+    MultiRun { records, timings }
+}
+
+/// Records of all parts that were run, and their timings if the run was timed or profiled.
+#[derive(Debug)]
+pub struct MultiRun {
+    pub records: Vec<Record>,
+    pub timings: Option<Timings>,
+}
+
+/// Records and buffered output of running the solution for a single day.
+#[derive(Debug, Default)]
+pub struct DayOutput {
+    pub records: Vec<Record>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Prints a summary of all parts that failed, panicked or timed out.
+fn print_failures(records: &[Record]) {
+    let failures: Vec<String> = records
+        .iter()
+        .filter_map(|record| {
+            let failure = record.failure.as_ref()?;
+            let part = if record.is_parse() {
+                "parse".into()
+            } else {
+                format!("part {}", record.part)
+            };
+            Some(format!(
+                "{} {part} {}",
+                record.puzzle.title(),
+                failure.kind.label()
+            ))
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        println!("\n{ANSI_BOLD}Failures:{ANSI_RESET} {}", failures.join(", "));
+    }
+}
+
+/// Receives the next message of a running day, failing with [`RecvTimeoutError::Timeout`] once `deadline` passed.
+fn recv_until<T>(receiver: &Receiver<T>, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+    match deadline {
+        Some(deadline) => receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    }
+}
+
+/// Creates the record of the part that was running when a day timed out, i.e. the first of the day's `parts` that
+/// did not finish.
+fn timeout_record(puzzle: Puzzle, parts: &[u8], records: &[Record], timeout: Duration) -> Record {
+    let part = parts
+        .iter()
+        .copied()
+        .find(|&part| records.iter().all(|record| record.part != part))
+        .or(parts.last().copied())
+        .unwrap_or(1);
+    failed_record(
+        puzzle,
+        part,
+        Stats::single(timeout),
+        Failure::timed_out(timeout),
+    )
+}
+
+/// Runs a day on a separate thread, so the run can move on if it times out.
+/// Threads of timed out days can't be stopped, they keep running in the background until the command exits.
+fn run_in_process(
+    registry: &Registry,
+    answers: &Arc<Answers>,
+    puzzle: Puzzle,
+    is_timed: bool,
+    format: OutputFormat,
+    timeout: Option<Duration>,
+) -> DayOutput {
+    let mut output = DayOutput::default();
+
+    let input = match try_read_file("inputs", puzzle) {
+        Ok(input) => input,
+        Err(e) => {
+            let _ = writeln!(
+                output.stderr,
+                "Could not read input for {}: {e}",
+                puzzle.title()
+            );
+            return output;
+        }
+    };
+
+    let (Some(runner), Some(parts)) = (registry.get(puzzle), registry.parts(puzzle)) else {
+        return output;
+    };
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let (sender, receiver) = mpsc::channel();
+    let answers = Arc::clone(answers);
+
+    thread::spawn(move || {
+        runner(&input, is_timed, &answers, &mut |record| {
+            // the receiver is gone if the day timed out.
+            let _ = sender.send(record);
+        });
+    });
+
+    loop {
+        let record = match recv_until(&receiver, deadline) {
+            Ok(record) => record,
+            Err(RecvTimeoutError::Timeout) => {
+                timeout_record(puzzle, parts, &output.records, timeout.unwrap_or_default())
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        let is_timed_out = record.failure.as_ref().map(|f| f.kind) == Some(FailureKind::Timeout);
+        output.stdout.push_str(&format_record(&record, format));
+        output.records.push(record);
+
+        if is_timed_out {
+            break;
+        }
+    }
+
+    output
+}
+
+/// Format a solution record in the requested output format, including a trailing newline.
+fn format_record(record: &Record, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text if record.is_parse() => format_parse(record),
+        OutputFormat::Text => format_record_text(&record.answer, record),
+        OutputFormat::Json => format!("{}\n", record.to_json_line()),
     }
 }
 
@@ -58,199 +327,344 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BrokenPipe => write!(f, "could not capture the output of the solution."),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(puzzle: Puzzle) -> String {
+    format!("./src/bin/{}.rs", puzzle.name())
 }
 
 /// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// This module encapsulates interaction with these binaries, both invoking them as well as collecting the records they emit.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{
+        format_record, get_path_for_bin, recv_until, timeout_record, DayOutput, Error, RunOptions,
+    };
+    use crate::template::{OutputFormat, Puzzle, Record, PARSE_PART};
     use std::{
+        collections::HashMap,
+        fmt::Write,
         io::{BufRead, BufReader},
         path::Path,
         process::{Command, Stdio},
+        sync::mpsc::{self, RecvTimeoutError},
         thread,
+        time::{Duration, Instant},
     };
+    use tinyjson::JsonValue;
+
+    /// Run the solution bin for a given day, whose `parts` are reported in order.
+    /// Used for days that can't run in the current process, see [`RunOptions::is_release`].
+    /// The solution is built before it runs, so the timeout doesn't include the time it takes cargo to build it.
+    pub fn run_solution(
+        puzzle: Puzzle,
+        parts: &[u8],
+        options: RunOptions,
+    ) -> Result<DayOutput, Error> {
+        let RunOptions {
+            is_release,
+            is_timed,
+            is_dhat,
+            format,
+            timeout,
+            ..
+        } = options;
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+        if !Path::new(&get_path_for_bin(puzzle)).exists() {
+            return Ok(DayOutput::default());
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
-
-        if is_release {
-            args.push("--release");
+        let bin_name = puzzle.name();
+        let mut build = Command::new("cargo");
+        build.args([
+            "build",
+            "--quiet",
+            "--message-format",
+            "json-render-diagnostics",
+            "--bin",
+            &bin_name,
+        ]);
+
+        if is_dhat {
+            build.args(["--profile", "dhat", "--features", "dhat-heap"]);
+        } else if is_release {
+            build.arg("--release");
         }
 
+        // children always emit JSON records, rendering happens here.
+        let mut args = vec!["--format", "json"];
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
+        build_and_run(build, &args, puzzle, parts, format, timeout)
+    }
+
+    /// Runs `build`, then the executable it reports with `args`, and collects the records it emits.
+    /// The deadline only starts once the executable is spawned, so neither compiling nor waiting for the build lock
+    /// of other days counts against the timeout.
+    fn build_and_run(
+        mut build: Command,
+        args: &[&str],
+        puzzle: Puzzle,
+        parts: &[u8],
+        format: OutputFormat,
+        timeout: Option<Duration>,
+    ) -> Result<DayOutput, Error> {
+        let build = build.output()?;
+
+        let mut output = DayOutput {
+            stderr: String::from_utf8_lossy(&build.stderr).into_owned(),
+            ..DayOutput::default()
+        };
+
+        let executable = String::from_utf8_lossy(&build.stdout)
+            .lines()
+            .find_map(|line| artifact_executable(&line.parse().ok()?));
+
+        // build errors were rendered to stderr already.
+        let Some(executable) = executable.filter(|_| build.status.success()) else {
+            return Ok(output);
+        };
+
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // buffer output while collecting records from stdout.
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
+        let mut cmd = Command::new(executable)
+            .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
 
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
-
         let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
-            });
+            stderr
+                .lines()
+                .map_while(Result::ok)
+                .map(|line| line + "\n")
+                .collect::<String>()
+        });
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+        loop {
+            let line = match recv_until(&receiver, deadline) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    cmd.kill()?;
+                    let record =
+                        timeout_record(puzzle, parts, &output.records, timeout.unwrap_or_default());
+                    output.stdout.push_str(&format_record(&record, format));
+                    output.records.push(record);
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            match (line.parse::<Record>(), format) {
+                (Ok(record), _) => {
+                    output.stdout.push_str(&format_record(&record, format));
+                    output.records.push(record);
+                }
+                // any other output of the solution, e.g. debug prints.
+                (Err(_), OutputFormat::Text) => {
+                    let _ = writeln!(output.stdout, "{line}");
+                }
+                // keep stdout machine-readable.
+                (Err(_), OutputFormat::Json) => {
+                    let _ = writeln!(output.stderr, "{line}");
+                }
+            }
         }
 
-        thread.join().unwrap();
+        output.stderr.push_str(&thread.join().unwrap());
         cmd.wait()?;
 
         Ok(output)
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
-        let mut timings = super::Timing {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+    /// Path of the executable in a `compiler-artifact` message of `cargo build --message-format json`.
+    fn artifact_executable(message: &JsonValue) -> Option<String> {
+        let message = message.get::<HashMap<String, JsonValue>>()?;
+
+        if message.get("reason")?.get::<String>()? != "compiler-artifact" {
+            return None;
+        }
+
+        message.get("executable")?.get::<String>().cloned()
+    }
+
+    /// Collect the records emitted by a solution into a [`super::Timing`].
+    pub fn collect_timing(records: &[Record], puzzle: Puzzle) -> super::Timing {
+        let mut timings = super::Timing::new(puzzle);
 
-        output
+        records
             .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
+            .filter(|record| record.answer.is_some() || record.is_parse())
+            .for_each(|record| match record.part {
+                PARSE_PART => {
+                    timings.parse = Some(record.stats.median);
+                    timings.parse_stats = Some(record.stats);
                 }
-
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+                1 => {
+                    timings.part_1 = Some(record.stats.median);
+                    timings.part_1_stats = Some(record.stats);
+                    timings.part_1_memory = record.memory;
                 }
-
-                timings.total_nanos += nanos;
+                2 => {
+                    timings.part_2 = Some(record.stats.median);
+                    timings.part_2_stats = Some(record.stats);
+                    timings.part_2_memory = record.memory;
+                }
+                _ => {}
             });
 
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
-    }
-
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
-    }
-
-    /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
-    #[cfg(feature = "test_lib")]
-    macro_rules! assert_approx_eq {
-        ($a:expr, $b:expr) => {{
-            let (a, b) = (&$a, &$b);
-            assert!(
-                (*a - *b).abs() < 1.0e-6,
-                "{} is not approximately equal to {}",
-                *a,
-                *b
-            );
-        }};
-    }
-
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{build_and_run, collect_timing, timeout_record};
+        use std::{process::Command, time::Duration};
+
+        use crate::{
+            day,
+            template::{answers::Verdict, output::Record, stats::Stats, OutputFormat, PARSE_PART},
+        };
 
-        use crate::day;
+        fn record(part: u8, answer: Option<&str>, median: Duration, samples: u128) -> Record {
+            Record {
+                puzzle: day!(1).into(),
+                part,
+                answer: answer.map(Into::into),
+                stats: Stats {
+                    samples,
+                    median,
+                    ..Stats::single(median)
+                },
+                memory: None,
+                failure: None,
+                verdict: Verdict::Unknown,
+            }
+        }
 
         #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
+        fn collects_execution_times() {
+            let res = collect_timing(
                 &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
+                    record(1, Some("0"), Duration::from_nanos(74), 100000),
+                    record(2, Some("10"), Duration::from_micros(74130), 99999),
                 ],
-                day!(1),
+                day!(1).into(),
             );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.total(), Duration::from_nanos(74130074));
+            assert_eq!(res.part_1.unwrap(), Duration::from_nanos(74));
+            assert_eq!(res.part_2.unwrap(), Duration::from_micros(74130));
+            assert_eq!(res.part_1_stats.unwrap().samples, 100000);
+            assert_eq!(res.part_2_stats.unwrap().samples, 99999);
         }
 
         #[test]
-        fn parses_with_patterns_in_input() {
-            let res = parse_exec_time(
+        fn collects_with_patterns_in_answers() {
+            let res = collect_timing(
                 &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
+                    record(1, Some("@ @ @ ( ) ms"), Duration::from_secs(2), 5),
+                    record(2, Some("10s"), Duration::from_millis(100), 1),
                 ],
-                day!(1),
+                day!(1).into(),
             );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.total(), Duration::from_millis(2100));
+            assert_eq!(res.part_1.unwrap(), Duration::from_secs(2));
+            assert_eq!(res.part_2.unwrap(), Duration::from_millis(100));
         }
 
         #[test]
-        fn parses_missing_parts() {
-            let res = parse_exec_time(
+        fn collects_missing_parts() {
+            let res = collect_timing(
                 &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
+                    record(1, None, Duration::from_nanos(10), 1),
+                    record(2, None, Duration::from_nanos(10), 1),
                 ],
-                day!(1),
+                day!(1).into(),
             );
-            assert_approx_eq!(res.total_nanos, 0_f64);
+            assert_eq!(res.total(), Duration::ZERO);
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn collects_parse_times() {
+            let res = collect_timing(
+                &[
+                    record(PARSE_PART, None, Duration::from_micros(5), 10),
+                    record(1, Some("0"), Duration::from_micros(20), 10),
+                ],
+                day!(1).into(),
+            );
+            assert_eq!(res.parse, Some(Duration::from_micros(5)));
+            assert_eq!(res.parse_stats.unwrap().samples, 10);
+            assert_eq!(res.total(), Duration::from_micros(25));
+        }
+
+        #[test]
+        fn labels_timed_out_parts() {
+            let timeout = Duration::from_secs(1);
+            let timed_out = |parts: &[u8], records: &[Record]| {
+                timeout_record(day!(1).into(), parts, records, timeout).part
+            };
+            let parsed = record(PARSE_PART, None, Duration::ZERO, 1);
+            let part_one = record(1, Some("0"), Duration::ZERO, 1);
+
+            assert_eq!(timed_out(&[1, 2], &[]), 1);
+            assert_eq!(timed_out(&[1, 2], &[part_one]), 2);
+            assert_eq!(timed_out(&[2], &[]), 2);
+            assert_eq!(timed_out(&[PARSE_PART, 2], &[]), PARSE_PART);
+            assert_eq!(timed_out(&[PARSE_PART, 2], &[parsed]), 2);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn starts_timeout_after_build() {
+            let mut build = Command::new("sh");
+            build.args([
+                "-c",
+                r#"sleep 1 && echo '{"reason":"compiler-artifact","executable":"echo"}'"#,
+            ]);
+            let line = record(1, Some("0"), Duration::ZERO, 1).to_json_line();
+
+            let output = build_and_run(
+                build,
+                &[&line],
+                day!(1).into(),
+                &[1, 2],
+                OutputFormat::Json,
+                Some(Duration::from_millis(500)),
+            )
+            .unwrap();
+
+            assert_eq!(output.records.len(), 1);
+            assert!(output.records[0].failure.is_none());
+        }
     }
 }
@@ -1,70 +1,259 @@
 /// Encapsulates code that interacts with solution functions.
+use std::error::Error;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::answers::{Answers, Verdict};
+use crate::template::aoc_client::{AocClient, Backend};
+use crate::template::failure::catch_panic;
+use crate::template::memory::{self, Memory};
+use crate::template::stats::Stats;
+use crate::template::submissions::{Outcome, Submissions};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, Puzzle, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Failure, FailureKind, OutputFormat, Record, PARSE_PART};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Return types of solution parts.
+///
+/// Parts return `Option<T>` if they might not have an answer, or `Result<T, E>` if they can fail with an error.
+pub trait PartOutput {
+    type Answer: Display;
+
+    /// Returns the answer of the part, if it has one.
+    fn answer(&self) -> Option<&Self::Answer>;
+
+    /// Returns the error of the part, if it failed.
+    fn failure(&self) -> Option<Failure>;
+}
+
+impl<T: Display> PartOutput for Option<T> {
+    type Answer = T;
+
+    fn answer(&self) -> Option<&T> {
+        self.as_ref()
+    }
+
+    fn failure(&self) -> Option<Failure> {
+        None
+    }
+}
+
+impl<T: Display, E: Error + 'static> PartOutput for Result<T, E> {
+    type Answer = T;
+
+    fn answer(&self) -> Option<&T> {
+        self.as_ref().ok()
+    }
+
+    fn failure(&self) -> Option<Failure> {
+        self.as_ref().err().map(|e| Failure::from_error(e))
+    }
+}
+
+/// Run a solution part and print its result, checking it against the known-good `answers`.
+/// A panic of the part is caught and reported like an error, so the remaining parts still run.
+pub fn run_part<I: Clone, R: PartOutput>(
+    func: impl Fn(I) -> R,
+    input: I,
+    puzzle: Puzzle,
+    part: u8,
+    answers: &Answers,
+) {
     let part_str = format!("Part {part}");
+    let format = OutputFormat::from_args();
+    let is_timed = env::args().any(|x| x == "--time");
+
+    let (result, stats, memory) = run_timed(func, input, is_timed, |result: &R| {
+        if format == OutputFormat::Text {
+            print_result(&result.answer(), &part_str, "");
+
+            if is_timed {
+                print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+                let _ = stdout().flush();
+            }
+        }
+    });
+
+    let record = create_record(&result, puzzle, part, stats, memory, answers);
+    let answer = result.as_ref().ok().and_then(PartOutput::answer);
+
+    match format {
+        OutputFormat::Text => print!("\r{}", format_record_text(&answer, &record)),
+        OutputFormat::Json => println!("{}", record.to_json_line()),
+    }
+
+    if let Some(answer) = answer {
+        submit_result(answer, puzzle, part, format);
+    }
+}
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+/// Parse the input of a solution, reporting the parse time as its own row.
+/// Returns the parsed input for the parts, or `None` if parsing failed.
+pub fn run_parse<T, E: Error + 'static>(
+    parser: impl Fn(&str) -> Result<T, E>,
+    input: &str,
+    puzzle: Puzzle,
+) -> Option<T> {
+    let format = OutputFormat::from_args();
+    let is_timed = env::args().any(|x| x == "--time");
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    if format == OutputFormat::Text && is_timed {
+        print!("Parse: {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout().flush();
+    }
+
+    let (parsed, record) = parse_timed(parser, input, puzzle, is_timed);
+
+    match format {
+        OutputFormat::Text => print!("\r{}", format_parse(&record)),
+        OutputFormat::Json => println!("{}", record.to_json_line()),
+    }
+
+    parsed
+}
+
+/// Parse the input of a solution without printing anything.
+/// Returns the parsed input for the parts, or `None` if parsing failed, and the [`Record`] of parsing.
+/// Used by the in-process [`Registry`](crate::template::registry::Registry).
+pub fn run_parse_in_process<T, E: Error + 'static>(
+    parser: impl Fn(&str) -> Result<T, E>,
+    input: &str,
+    puzzle: Puzzle,
+    is_timed: bool,
+) -> (Option<T>, Record) {
+    parse_timed(parser, input, puzzle, is_timed)
+}
+
+fn parse_timed<T, E: Error + 'static>(
+    parser: impl Fn(&str) -> Result<T, E>,
+    input: &str,
+    puzzle: Puzzle,
+    is_timed: bool,
+) -> (Option<T>, Record) {
+    let (parsed, stats, memory) = run_timed(parser, input, is_timed, |_| {});
+
+    let (parsed, failure) = match parsed {
+        Ok(Ok(parsed)) => (Some(parsed), None),
+        Ok(Err(e)) => (None, Some(Failure::from_error(&e))),
+        Err(failure) => (None, Some(failure)),
+    };
+
+    let record = Record {
+        puzzle,
+        part: PARSE_PART,
+        answer: None,
+        stats,
+        memory,
+        failure,
+        verdict: Verdict::Unknown,
+    };
+
+    (parsed, record)
+}
+
+/// Run a solution part without printing anything and return its [`Record`].
+/// Used by the in-process [`Registry`](crate::template::registry::Registry).
+pub fn run_part_in_process<I: Clone, R: PartOutput>(
+    func: impl Fn(I) -> R,
+    input: I,
+    puzzle: Puzzle,
+    part: u8,
+    is_timed: bool,
+    answers: &Answers,
+) -> Record {
+    let (result, stats, memory) = run_timed(func, input, is_timed, |_| {});
+    create_record(&result, puzzle, part, stats, memory, answers)
+}
+
+fn create_record<R: PartOutput>(
+    result: &Result<R, Failure>,
+    puzzle: Puzzle,
+    part: u8,
+    stats: Stats,
+    memory: Option<Memory>,
+    answers: &Answers,
+) -> Record {
+    let result = match result {
+        Ok(result) => result,
+        Err(failure) => return failed_record(puzzle, part, stats, failure.clone()),
+    };
+
+    let answer = result.answer().map(ToString::to_string);
+    let verdict = answers.verdict(puzzle, part, answer.as_deref());
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    Record {
+        puzzle,
+        part,
+        answer,
+        stats,
+        memory,
+        failure: result.failure(),
+        verdict,
+    }
+}
+
+/// Creates the record of a part that did not produce a result, e.g. because it panicked or timed out.
+pub(crate) fn failed_record(puzzle: Puzzle, part: u8, stats: Stats, failure: Failure) -> Record {
+    Record {
+        puzzle,
+        part,
+        answer: None,
+        stats,
+        memory: None,
+        failure: Some(failure),
+        verdict: Verdict::Unknown,
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// With the `dhat-heap` feature, the heap usage of the first execution is captured.
+/// If the function panics, the panic is returned as a [`Failure`] and the function is not benched.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    is_timed: bool,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (Result<T, Failure>, Stats, Option<Memory>) {
     let timer = Instant::now();
-    let result = {
+    let (result, memory) = {
         let input = input.clone();
-
-        #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
-
-        func(input)
+        memory::profile(|| catch_panic(|| func(input)))
     };
     let base_time = timer.elapsed();
 
+    let result = match result {
+        Ok(result) => result,
+        Err(failure) => return (Err(failure), Stats::single(base_time), memory),
+    };
+
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
-    } else {
-        (base_time, 1)
-    };
+    if !is_timed {
+        return (Ok(result), Stats::single(base_time), memory);
+    }
 
-    (result, run.0, run.1)
+    match catch_panic(|| bench(func, input, &base_time)) {
+        Ok(stats) => (Ok(result), stats, memory),
+        Err(failure) => (Err(failure), Stats::single(base_time), memory),
+    }
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> Stats {
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    // discard a few iterations to warm up caches and the branch predictor before sampling.
+    let warmup_iterations = (bench_iterations / 10).clamp(1, 100);
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    for _ in 0..warmup_iterations {
+        black_box(func(black_box(input.clone())));
+    }
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -76,72 +265,135 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    Stats::from_samples(&timers).unwrap()
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
-}
+pub(crate) fn format_duration(stats: &Stats) -> String {
+    let Stats {
+        samples,
+        median,
+        min,
+        max,
+        std_dev,
+        p95,
+        outliers,
+        ..
+    } = stats;
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
+    if *samples == 1 {
+        format!(" ({median:.1?})")
     } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+        format!(
+            " ({median:.1?} @ {samples} samples; min {min:.1?}, max {max:.1?}, σ {std_dev:.1?}, p95 {p95:.1?}, {outliers} outliers)"
+        )
+    }
+}
+
+/// Formats the verdict, timing and heap usage of a record as a suffix for its printed result.
+pub(crate) fn format_annotation(record: &Record) -> String {
+    let mut annotation = record.verdict.format() + &format_duration(&record.stats);
+    if let Some(memory) = &record.memory {
+        annotation.push_str(&memory.format());
+    }
+    annotation
+}
+
+/// Formats the output line of parsing the input, including a trailing newline.
+pub(crate) fn format_parse(record: &Record) -> String {
+    let mut line = String::from("Parse:");
+    if let Some(failure) = &record.failure {
+        line.push_str(&format_failure_label(failure));
+    }
+    line.push_str(&format_duration(&record.stats));
+    if let Some(memory) = &record.memory {
+        line.push_str(&memory.format());
+    }
+    // overwrite the `benching` placeholder.
+    line.push_str("          \n");
+
+    if let Some(failure) = &record.failure {
+        line.push_str(&failure.format());
+    }
+    line
+}
+
+/// Formats the final output lines of a solution part, including the error of failed parts.
+pub(crate) fn format_record_text<T: Display>(answer: &Option<T>, record: &Record) -> String {
+    let part_str = format!("Part {}", record.part);
+
+    match &record.failure {
+        Some(failure) => format!(
+            "{part_str}:{}{}\n{}",
+            format_failure_label(failure),
+            format_duration(&record.stats),
+            failure.format()
+        ),
+        None => format_result(answer, &part_str, &format_annotation(record)),
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+/// Marks a failed part, e.g. ` ✖ panicked`. Parts that returned an error are only marked with the cross.
+fn format_failure_label(failure: &Failure) -> String {
+    match failure.kind {
+        FailureKind::Error => " ✖".into(),
+        kind => format!(" ✖ {}", kind.label()),
+    }
+}
+
+pub(crate) fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
+    if !is_intermediate_result {
+        print!("\r{}", format_result(result, part, duration_str));
+        return;
+    }
+
     match result {
         Some(result) => {
             if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
-                if is_intermediate_result {
-                    print!("{str}");
-                } else {
-                    print!("\r");
-                    println!("{str}");
-                    println!("{result}");
-                }
+                print!("{part}: ▼ ");
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
-                if is_intermediate_result {
-                    print!("{str}");
-                } else {
-                    print!("\r");
-                    println!("{str}");
-                }
+                print!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}");
             }
         }
         None => {
-            if is_intermediate_result {
-                print!("{part}: ✖");
+            print!("{part}: ✖");
+        }
+    }
+}
+
+/// Formats the final output lines for a solution part, including a trailing newline.
+pub(crate) fn format_result<T: Display>(
+    result: &Option<T>,
+    part: &str,
+    duration_str: &str,
+) -> String {
+    match result {
+        Some(result) => {
+            let result = result.to_string();
+            if result.contains('\n') {
+                format!("{part}: ▼ {duration_str}\n{result}\n")
             } else {
-                print!("\r");
-                println!("{part}: ✖             ");
+                format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}\n")
             }
         }
+        None => format!("{part}: ✖             \n"),
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+///  2. a session cookie is configured (or aoc-cli is installed, if it is the selected backend).
+///  3. the answer was not submitted before and is within the bounds of previous submissions.
+///
+/// The outcome of the submission is stored in `data/submissions.json`, correct answers in `data/answers.json`.
+/// With `--format json`, progress and the response are printed to stderr to keep stdout machine-readable.
 fn submit_result<T: Display>(
     result: T,
-    day: Day,
+    puzzle: Puzzle,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+    format: OutputFormat,
+) -> Option<Result<String, String>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -164,11 +416,70 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+    let answer = result.to_string();
+    let mut submissions = Submissions::read_from_file();
+
+    if let Err(refusal) = submissions.check(puzzle, part, &answer) {
+        eprintln!("Refusing to submit \"{answer}\": {refusal}.");
+        return None;
+    }
+
+    let status = |message: &str| match format {
+        OutputFormat::Text => print!("{message}"),
+        OutputFormat::Json => eprint!("{message}"),
+    };
+
+    let response = match Backend::from_env() {
+        Backend::Native => {
+            status("Submitting result...\n");
+            AocClient::from_env()
+                .and_then(|client| client.submit(puzzle, part, &answer))
+                .map_err(|e| e.to_string())
+        }
+        Backend::AocCli => {
+            if aoc_cli::check().is_err() {
+                eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+                process::exit(1);
+            }
+
+            status("Submitting result via aoc-cli...\n");
+            aoc_cli::submit(puzzle, part, &answer)
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+                .map_err(|e| match &e {
+                    // the response explains why the submission failed.
+                    aoc_cli::AocCommandError::BadExitStatus(output) => {
+                        format!("{e} {}", String::from_utf8_lossy(&output.stdout).trim())
+                    }
+                    _ => e.to_string(),
+                })
+        }
+    };
+
+    match &response {
+        Ok(response) => {
+            status(response);
+            let outcome = Outcome::from_response(response);
+            status(&format!(
+                "Submission outcome: {ANSI_BOLD}{outcome}{ANSI_RESET}\n"
+            ));
+
+            if outcome.is_verdict() {
+                submissions.record(puzzle, part, &answer, outcome);
+                if let Err(e) = submissions.store_file() {
+                    eprintln!("Failed to store submission: {e}");
+                }
+            }
+
+            if outcome == Outcome::Correct {
+                let mut answers = Answers::read_from_file();
+                answers.set(puzzle, part, &answer);
+                if let Err(e) = answers.store_file() {
+                    eprintln!("Failed to store answer: {e}");
+                }
+            }
+        }
+        Err(e) => eprintln!("Failed to submit: {e}"),
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(response)
 }
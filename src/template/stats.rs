@@ -0,0 +1,176 @@
+/// Summary statistics for benchmark samples.
+use std::{collections::HashMap, time::Duration};
+use tinyjson::JsonValue;
+
+/// Statistics collected while benching a single solution part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: u128,
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+    pub p95: Duration,
+    /// Number of samples outside of the Tukey fences (1.5 × IQR below Q1 or above Q3).
+    pub outliers: u128,
+}
+
+impl Stats {
+    /// Computes statistics for a set of samples. Returns [`None`] if `samples` is empty.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<u128> = samples.iter().map(Duration::as_nanos).collect();
+        sorted.sort_unstable();
+
+        let len = sorted.len() as u128;
+
+        #[allow(clippy::cast_precision_loss)]
+        let variance = {
+            let mean = sorted.iter().sum::<u128>() as f64 / len as f64;
+            sorted
+                .iter()
+                .map(|x| (*x as f64 - mean).powi(2))
+                .sum::<f64>()
+                / len as f64
+        };
+
+        let q1 = percentile(&sorted, 25);
+        let q3 = percentile(&sorted, 75);
+        let iqr = q3 - q1;
+        let lower_fence = q1.saturating_sub(iqr * 3 / 2);
+        let upper_fence = q3 + iqr * 3 / 2;
+
+        let outliers = sorted
+            .iter()
+            .filter(|x| **x < lower_fence || **x > upper_fence)
+            .count() as u128;
+
+        Some(Self {
+            samples: len,
+            median: nanos_to_duration(percentile(&sorted, 50)),
+            min: nanos_to_duration(sorted[0]),
+            max: nanos_to_duration(sorted[sorted.len() - 1]),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+            p95: nanos_to_duration(percentile(&sorted, 95)),
+            outliers,
+        })
+    }
+
+    /// Statistics for a part that was executed exactly once.
+    pub fn single(duration: Duration) -> Self {
+        Self::from_samples(&[duration]).unwrap()
+    }
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[u128], p: usize) -> u128 {
+    let rank = (sorted.len() * p).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn nanos_to_duration(nanos: u128) -> Duration {
+    Duration::from_nanos(nanos as u64)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Stats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Stats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        map.insert("outliers".into(), JsonValue::Number(value.outliers as f64));
+
+        for (key, duration) in [
+            ("median_nanos", value.median),
+            ("min_nanos", value.min),
+            ("max_nanos", value.max),
+            ("std_dev_nanos", value.std_dev),
+            ("p95_nanos", value.p95),
+        ] {
+            map.insert(key.into(), JsonValue::Number(duration.as_nanos() as f64));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Stats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stats to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected stats.{key} to be a number."))
+        };
+        let duration = |key: &str| number(key).map(|x| Duration::from_nanos(x as u64));
+
+        Ok(Stats {
+            samples: number("samples")? as u128,
+            median: duration("median_nanos")?,
+            min: duration("min_nanos")?,
+            max: duration("max_nanos")?,
+            std_dev: duration("std_dev_nanos")?,
+            p95: duration("p95_nanos")?,
+            outliers: number("outliers")? as u128,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    fn nanos(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_nanos).collect()
+    }
+
+    #[test]
+    fn handles_empty_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn computes_summary() {
+        let stats = Stats::from_samples(&nanos(&[5, 1, 4, 2, 3])).unwrap();
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.median, Duration::from_nanos(3));
+        assert_eq!(stats.min, Duration::from_nanos(1));
+        assert_eq!(stats.max, Duration::from_nanos(5));
+        assert_eq!(stats.p95, Duration::from_nanos(5));
+        assert_eq!(stats.std_dev, Duration::from_nanos(1));
+        assert_eq!(stats.outliers, 0);
+    }
+
+    #[test]
+    fn median_ignores_noisy_sample() {
+        let stats = Stats::from_samples(&nanos(&[10, 10, 11, 10, 9, 10, 10_000])).unwrap();
+        assert_eq!(stats.median, Duration::from_nanos(10));
+        assert_eq!(stats.max, Duration::from_nanos(10_000));
+        assert_eq!(stats.outliers, 1);
+    }
+
+    #[test]
+    fn handles_single_sample() {
+        let stats = Stats::single(Duration::from_nanos(42));
+        assert_eq!(stats.samples, 1);
+        assert_eq!(stats.median, Duration::from_nanos(42));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+        assert_eq!(stats.outliers, 0);
+    }
+}
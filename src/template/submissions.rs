@@ -0,0 +1,404 @@
+use std::{collections::HashMap, fmt::Display, fs, io::Error, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::Puzzle;
+
+static SUBMISSIONS_FILE_PATH: &str = "./data/submissions.json";
+
+/// The outcome of submitting an answer, as reported by advent of code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// The answer is wrong, but no hint about its magnitude was given.
+    Incorrect,
+    /// The answer was not checked because another answer was submitted too recently.
+    Wait(Option<Duration>),
+    /// The response could not be interpreted, e.g. because the part was already solved.
+    Unknown,
+}
+
+impl Outcome {
+    /// Interprets the response text returned after submitting an answer.
+    pub fn from_response(response: &str) -> Self {
+        if response.contains("That's the right answer") {
+            Outcome::Correct
+        } else if response.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if response.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if response.contains("That's not the right answer") {
+            Outcome::Incorrect
+        } else if response.contains("You gave an answer too recently") {
+            Outcome::Wait(parse_wait_time(response))
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    /// Whether the outcome tells us something about the submitted answer.
+    pub fn is_verdict(self) -> bool {
+        matches!(
+            self,
+            Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Incorrect
+        )
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too_high",
+            Outcome::TooLow => "too_low",
+            Outcome::Incorrect => "incorrect",
+            Outcome::Wait(_) => "wait",
+            Outcome::Unknown => "unknown",
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => f.write_str("correct"),
+            Outcome::TooHigh => f.write_str("too high"),
+            Outcome::TooLow => f.write_str("too low"),
+            Outcome::Incorrect => f.write_str("incorrect"),
+            Outcome::Wait(Some(duration)) => write!(f, "wait {}s", duration.as_secs()),
+            Outcome::Wait(None) => f.write_str("wait"),
+            Outcome::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
+/// Parses e.g. "You have 1m 5s left to wait." into a duration.
+fn parse_wait_time(response: &str) -> Option<Duration> {
+    let start = response.find("You have ")? + "You have ".len();
+    let end = start + response[start..].find(" left to wait")?;
+
+    response[start..end]
+        .split_whitespace()
+        .try_fold(0, |secs, token| {
+            if let Some(minutes) = token.strip_suffix('m') {
+                Some(secs + minutes.parse::<u64>().ok()? * 60)
+            } else {
+                Some(secs + token.strip_suffix('s')?.parse::<u64>().ok()?)
+            }
+        })
+        .map(Duration::from_secs)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A single answer that was submitted and checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submission {
+    pub puzzle: Puzzle,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Represents all checked submissions.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Submissions {
+    pub data: Vec<Submission>,
+}
+
+/// Reasons to not submit an answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadySubmitted(Outcome),
+    NotBelow(String),
+    NotAbove(String),
+    AlreadySolved(String),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::AlreadySubmitted(outcome) => {
+                write!(f, "this answer was already submitted and was {outcome}")
+            }
+            Refusal::NotBelow(bound) => write!(f, "{bound} was too high, the answer must be lower"),
+            Refusal::NotAbove(bound) => write!(f, "{bound} was too low, the answer must be higher"),
+            Refusal::AlreadySolved(answer) => {
+                write!(f, "this part was already solved with {answer}")
+            }
+        }
+    }
+}
+
+impl Submissions {
+    /// Dehydrate submissions to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(SUBMISSIONS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate submissions from a JSON file. If not present, returns empty submissions.
+    pub fn read_from_file() -> Self {
+        let Ok(s) = fs::read_to_string(SUBMISSIONS_FILE_PATH) else {
+            return Submissions::default();
+        };
+
+        match Submissions::try_from(s) {
+            Ok(submissions) => submissions,
+            Err(e) => {
+                eprintln!("{e}");
+                Submissions::default()
+            }
+        }
+    }
+
+    /// Remembers a submission if its outcome tells us something about the answer.
+    pub fn record(&mut self, puzzle: Puzzle, part: u8, answer: &str, outcome: Outcome) {
+        if !outcome.is_verdict() {
+            return;
+        }
+
+        self.data
+            .retain(|s| !(s.puzzle == puzzle && s.part == part && s.answer == answer));
+        self.data.push(Submission {
+            puzzle,
+            part,
+            answer: answer.into(),
+            outcome,
+        });
+    }
+
+    /// Checks whether `answer` is worth submitting, given the previous submissions for a part.
+    pub fn check(&self, puzzle: Puzzle, part: u8, answer: &str) -> Result<(), Refusal> {
+        let submissions = self
+            .data
+            .iter()
+            .filter(|s| s.puzzle == puzzle && s.part == part);
+
+        let mut lowest_too_high: Option<(i128, &str)> = None;
+        let mut highest_too_low: Option<(i128, &str)> = None;
+
+        for submission in submissions {
+            if submission.outcome == Outcome::Correct {
+                return Err(Refusal::AlreadySolved(submission.answer.clone()));
+            }
+
+            if submission.answer == answer {
+                return Err(Refusal::AlreadySubmitted(submission.outcome));
+            }
+
+            let Ok(value) = submission.answer.parse::<i128>() else {
+                continue;
+            };
+
+            match submission.outcome {
+                Outcome::TooHigh if lowest_too_high.is_none_or(|(b, _)| value < b) => {
+                    lowest_too_high = Some((value, &submission.answer));
+                }
+                Outcome::TooLow if highest_too_low.is_none_or(|(b, _)| value > b) => {
+                    highest_too_low = Some((value, &submission.answer));
+                }
+                _ => {}
+            }
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            if let Some((bound, bound_str)) = lowest_too_high {
+                if value >= bound {
+                    return Err(Refusal::NotBelow(bound_str.into()));
+                }
+            }
+            if let Some((bound, bound_str)) = highest_too_low {
+                if value <= bound {
+                    return Err(Refusal::NotAbove(bound_str.into()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Submissions> for JsonValue {
+    fn from(value: Submissions) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Submissions {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Submissions {
+            data: json_data
+                .iter()
+                .map(Submission::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submission> for JsonValue {
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        value.puzzle.insert_json(&mut map);
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        map.insert(
+            "outcome".into(),
+            JsonValue::String(value.outcome.as_str().into()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Submission {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let puzzle = Puzzle::from_json(json)
+            .ok_or("Expected submission.day to be a Day struct and submission.year a number.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u8)
+            .ok_or("Expected submission.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.answer to be a string.")?;
+
+        let outcome = match json.get("outcome").and_then(|v| v.get::<String>()) {
+            Some(v) if v == "correct" => Outcome::Correct,
+            Some(v) if v == "too_high" => Outcome::TooHigh,
+            Some(v) if v == "too_low" => Outcome::TooLow,
+            Some(v) if v == "incorrect" => Outcome::Incorrect,
+            _ => return Err("Expected submission.outcome to be a checked outcome.".into()),
+        };
+
+        Ok(Submission {
+            puzzle,
+            part,
+            answer: answer.clone(),
+            outcome,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Outcome, Refusal, Submissions};
+    use crate::day;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(
+            Outcome::from_response("That's the right answer! You are one gold star closer."),
+            Outcome::Correct
+        );
+        assert_eq!(
+            Outcome::from_response(
+                "That's not the right answer; your answer is too high. Please wait one minute."
+            ),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            Outcome::from_response("That's not the right answer; your answer is too low."),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            Outcome::from_response("That's not the right answer. If you're stuck, ..."),
+            Outcome::Incorrect
+        );
+        assert_eq!(
+            Outcome::from_response("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 1m 5s left to wait."),
+            Outcome::Wait(Some(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            Outcome::from_response("You don't seem to be solving the right level."),
+            Outcome::Unknown
+        );
+    }
+
+    #[test]
+    fn refuses_known_submissions() {
+        let mut submissions = Submissions::default();
+        submissions.record(day!(1).into(), 1, "100", Outcome::TooHigh);
+        submissions.record(day!(1).into(), 1, "10", Outcome::TooLow);
+        submissions.record(day!(1).into(), 1, "abc", Outcome::Incorrect);
+        submissions.record(day!(1).into(), 1, "50", Outcome::Wait(None));
+
+        assert_eq!(
+            submissions.check(day!(1).into(), 1, "abc"),
+            Err(Refusal::AlreadySubmitted(Outcome::Incorrect))
+        );
+        assert_eq!(
+            submissions.check(day!(1).into(), 1, "150"),
+            Err(Refusal::NotBelow("100".into()))
+        );
+        assert_eq!(
+            submissions.check(day!(1).into(), 1, "10"),
+            Err(Refusal::AlreadySubmitted(Outcome::TooLow))
+        );
+        assert_eq!(
+            submissions.check(day!(1).into(), 1, "-3"),
+            Err(Refusal::NotAbove("10".into()))
+        );
+        assert_eq!(submissions.check(day!(1).into(), 1, "50"), Ok(()));
+        assert_eq!(submissions.check(day!(1).into(), 2, "150"), Ok(()));
+    }
+
+    #[test]
+    fn refuses_solved_parts() {
+        let mut submissions = Submissions::default();
+        submissions.record(day!(1).into(), 1, "42", Outcome::Correct);
+        assert_eq!(
+            submissions.check(day!(1).into(), 1, "43"),
+            Err(Refusal::AlreadySolved("42".into()))
+        );
+    }
+
+    #[test]
+    fn roundtrips_submissions() {
+        let mut submissions = Submissions::default();
+        submissions.record(day!(3).into(), 2, "7", Outcome::TooLow);
+        let json = tinyjson::JsonValue::from(submissions.clone())
+            .stringify()
+            .unwrap();
+        assert_eq!(Submissions::try_from(json).unwrap().data, submissions.data);
+    }
+}
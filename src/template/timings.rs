@@ -1,20 +1,30 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fs, io::Error, str::FromStr, time::Duration};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::{
+    memory::{parse_optional_memory, Memory},
+    stats::Stats,
+    Puzzle,
+};
 
 static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 
-/// Represents benchmark times for a single day.
+/// Represents benchmark times for a single puzzle.
+/// Durations are medians, `parse` is only set for solutions that parse their input with a parser.
 #[derive(Clone, Debug)]
 pub struct Timing {
-    pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
-    pub total_nanos: f64,
+    pub puzzle: Puzzle,
+    pub parse: Option<Duration>,
+    pub part_1: Option<Duration>,
+    pub part_2: Option<Duration>,
+    pub parse_stats: Option<Stats>,
+    pub part_1_stats: Option<Stats>,
+    pub part_2_stats: Option<Stats>,
+    pub part_1_memory: Option<Memory>,
+    pub part_2_memory: Option<Memory>,
 }
 
-/// Represents benchmark times for a set of days.
+/// Represents benchmark times for a set of puzzles.
 /// Can be serialized from / to JSON.
 #[derive(Clone, Debug, Default)]
 pub struct Timings {
@@ -45,32 +55,103 @@ impl Timings {
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
+    /// Memory is only captured by DHAT runs, so it is kept if `other` doesn't have it.
     pub fn merge(&self, new: &Self) -> Self {
         let mut data: Vec<Timing> = vec![];
 
         for timing in &new.data {
-            data.push(timing.clone());
+            let mut timing = timing.clone();
+            if let Some(previous) = self.data.iter().find(|t| t.puzzle == timing.puzzle) {
+                timing.part_1_memory = timing.part_1_memory.or(previous.part_1_memory);
+                timing.part_2_memory = timing.part_2_memory.or(previous.part_2_memory);
+            }
+            data.push(timing);
         }
 
         for timing in &self.data {
-            if !data.iter().any(|t| t.day == timing.day) {
+            if !data.iter().any(|t| t.puzzle == timing.puzzle) {
                 data.push(timing.clone());
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.puzzle);
         Timings { data }
     }
 
+    /// Merge the memory of a DHAT run into `self`, keeping the stored durations.
+    /// Durations of profiled builds are skewed by the profiler, so puzzles without stored timings get none.
+    pub fn merge_memory(&self, new: &Self) -> Self {
+        let memory_only = new
+            .data
+            .iter()
+            .map(|timing| {
+                let mut stored = self
+                    .data
+                    .iter()
+                    .find(|t| t.puzzle == timing.puzzle)
+                    .cloned()
+                    .unwrap_or_else(|| Timing::new(timing.puzzle));
+                stored.part_1_memory = timing.part_1_memory;
+                stored.part_2_memory = timing.part_2_memory;
+                stored
+            })
+            .collect();
+
+        self.merge(&Timings { data: memory_only })
+    }
+
+    /// Returns `true` if any part has captured memory.
+    pub fn has_memory(&self) -> bool {
+        self.data
+            .iter()
+            .any(|t| t.part_1_memory.is_some() || t.part_2_memory.is_some())
+    }
+
+    /// Sum up total duration of timings.
+    pub fn total(&self) -> Duration {
+        self.data.iter().map(Timing::total).sum()
+    }
+
     /// Sum up total duration of timings as millis.
     pub fn total_millis(&self) -> f64 {
-        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
+        self.total().as_secs_f64() * 1000_f64
     }
 
-    pub fn is_day_complete(&self, day: Day) -> bool {
+    /// Returns `true` if any puzzle has a parse time.
+    pub fn has_parse(&self) -> bool {
+        self.data.iter().any(|t| t.parse.is_some())
+    }
+
+    pub fn is_day_complete(&self, puzzle: Puzzle) -> bool {
         self.data
             .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+            .any(|t| t.puzzle == puzzle && t.part_1.is_some() && t.part_2.is_some())
+    }
+}
+
+impl Timing {
+    /// Creates a timing of `puzzle` without any durations, stats or memory.
+    #[must_use]
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self {
+            puzzle,
+            parse: None,
+            part_1: None,
+            part_2: None,
+            parse_stats: None,
+            part_1_stats: None,
+            part_2_stats: None,
+            part_1_memory: None,
+            part_2_memory: None,
+        }
+    }
+
+    /// Sum of the parse and part durations.
+    pub fn total(&self) -> Duration {
+        [self.parse, self.part_1, self.part_2]
+            .into_iter()
+            .flatten()
+            .sum()
     }
 }
 
@@ -118,26 +199,55 @@ impl From<&Timing> for JsonValue {
     fn from(value: &Timing) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
-        map.insert("day".into(), JsonValue::String(value.day.to_string()));
-        map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
+        value.puzzle.insert_json(&mut map);
 
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
+        #[allow(clippy::cast_precision_loss)]
+        let nanos = |d: Option<Duration>| {
+            d.map_or(JsonValue::Null, |d| JsonValue::Number(d.as_nanos() as f64))
+        };
+
+        map.insert("parse_nanos".into(), nanos(value.parse));
+        map.insert("part_1_nanos".into(), nanos(value.part_1));
+        map.insert("part_2_nanos".into(), nanos(value.part_2));
 
         map.insert(
-            "part_1".into(),
-            match part_1 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            "parse_stats".into(),
+            value
+                .parse_stats
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
         );
 
         map.insert(
-            "part_2".into(),
-            match part_2 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            "part_1_stats".into(),
+            value
+                .part_1_stats
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_2_stats".into(),
+            value
+                .part_2_stats
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_1_memory".into(),
+            value
+                .part_1_memory
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "part_2_memory".into(),
+            value
+                .part_2_memory
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
         );
 
         JsonValue::Object(map)
@@ -152,41 +262,112 @@ impl TryFrom<&JsonValue> for Timing {
             .get::<HashMap<String, JsonValue>>()
             .ok_or("Expected timing to be a JSON object.")?;
 
-        let day = json
-            .get("day")
-            .and_then(|v| v.get::<String>())
-            .and_then(|day| Day::from_str(day).ok())
-            .ok_or("Expected timing.day to be a Day struct.")?;
+        let puzzle = Puzzle::from_json(json)
+            .ok_or("Expected timing.day to be a Day struct and timing.year a number.")?;
+
+        let parse = parse_optional_duration(json, "parse_nanos", None)?;
+        let part_1 = parse_optional_duration(json, "part_1_nanos", Some("part_1"))?;
+        let part_2 = parse_optional_duration(json, "part_2_nanos", Some("part_2"))?;
+
+        // NOTE: stats were added later, so older timing files might not contain them.
+        let part_1_stats = parse_optional_stats(json.get("part_1_stats")).or(Err(
+            "Expected timing.part_1_stats to be null or stats object.",
+        ))?;
 
-        let part_1 = json
-            .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
+        let part_2_stats = parse_optional_stats(json.get("part_2_stats")).or(Err(
+            "Expected timing.part_2_stats to be null or stats object.",
+        ))?;
 
-        let part_2 = json
-            .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
+        let parse_stats = parse_optional_stats(json.get("parse_stats")).or(Err(
+            "Expected timing.parse_stats to be null or stats object.",
+        ))?;
 
-        let total_nanos = json
-            .get("total_nanos")
-            .and_then(|v| v.get::<f64>().copied())
-            .ok_or("Expected timing.total_nanos to be a number.")?;
+        // NOTE: memory is only captured in DHAT builds.
+        let part_1_memory = parse_optional_memory(json.get("part_1_memory")).or(Err(
+            "Expected timing.part_1_memory to be null or memory object.",
+        ))?;
+
+        let part_2_memory = parse_optional_memory(json.get("part_2_memory")).or(Err(
+            "Expected timing.part_2_memory to be null or memory object.",
+        ))?;
 
         Ok(Timing {
-            day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
-            total_nanos,
+            puzzle,
+            parse,
+            part_1,
+            part_2,
+            parse_stats,
+            part_1_stats,
+            part_2_stats,
+            part_1_memory,
+            part_2_memory,
         })
     }
 }
 
-/* -------------------------------------------------------------------------- */
+/// Reads a duration in nanoseconds from `key`.
+/// Older timing files store pre-formatted durations like `74.1ms` in `legacy_key` instead, these are parsed as a fallback.
+fn parse_optional_duration(
+    json: &HashMap<String, JsonValue>,
+    key: &str,
+    legacy_key: Option<&str>,
+) -> Result<Option<Duration>, String> {
+    if let Some(value) = json.get(key) {
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        return value
+            .get::<f64>()
+            .map(|nanos| Some(Duration::from_nanos(*nanos as u64)))
+            .ok_or(format!("Expected timing.{key} to be null or a number."));
+    }
+
+    let Some(legacy_key) = legacy_key else {
+        return Ok(None);
+    };
+
+    match json.get(legacy_key) {
+        Some(value) if !value.is_null() => value
+            .get::<String>()
+            .and_then(|s| parse_formatted_duration(s))
+            .map(Some)
+            .ok_or(format!(
+                "Expected timing.{legacy_key} to be null or a duration string."
+            )),
+        _ => Ok(None),
+    }
+}
+
+/// Parses a duration formatted with `{:.1?}`, e.g. `74.1ms`.
+fn parse_formatted_duration(s: &str) -> Option<Duration> {
+    let index = s.find(|c: char| c.is_alphabetic())?;
+    let (value, unit) = s.split_at(index);
+    let value: f64 = value.parse().ok()?;
+
+    let secs = match unit {
+        "ns" => value / 1e9,
+        "µs" => value / 1e6,
+        "ms" => value / 1e3,
+        "s" => value,
+        _ => return None,
+    };
+
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+fn parse_optional_stats(value: Option<&JsonValue>) -> Result<Option<Stats>, String> {
+    match value {
+        Some(v) if !v.is_null() => Stats::try_from(v).map(Some),
+        _ => Ok(None),
+    }
+}
 
 #[cfg(feature = "test_lib")]
 mod tests {
     use crate::day;
+    use std::time::Duration;
 
     use super::{Timing, Timings};
 
@@ -194,22 +375,18 @@ mod tests {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    part_1: Some(Duration::from_millis(10)),
+                    part_2: Some(Duration::from_millis(20)),
+                    ..Timing::new(day!(1).into())
                 },
                 Timing {
-                    day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    part_1: Some(Duration::from_millis(30)),
+                    part_2: Some(Duration::from_millis(40)),
+                    ..Timing::new(day!(2).into())
                 },
                 Timing {
-                    day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: None,
-                    total_nanos: 4e+10,
+                    part_1: Some(Duration::from_millis(40)),
+                    ..Timing::new(day!(4).into())
                 },
             ],
         }
@@ -217,17 +394,60 @@ mod tests {
 
     mod deserialization {
         use crate::{day, template::timings::Timings};
+        use std::time::Duration;
 
         #[test]
         fn handles_json_timings() {
-            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let json = r#"{ "data": [{ "day": "01", "parse_nanos": 500, "part_1_nanos": 1000000, "part_2_nanos": null }] }"#.to_string();
             let timings = Timings::try_from(json).unwrap();
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
-            assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
+            assert_eq!(timing.puzzle.day, day!(1));
+            assert_eq!(timing.parse, Some(Duration::from_nanos(500)));
+            assert_eq!(timing.part_1, Some(Duration::from_millis(1)));
             assert_eq!(timing.part_2, None);
-            assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+            assert_eq!(timing.total(), Duration::from_nanos(1_000_500));
+        }
+
+        #[test]
+        fn handles_legacy_json_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "74.1ms", "part_2": "39.0ns", "total_nanos": 74100039 }, { "day": "02", "part_1": "1.2µs", "part_2": null, "total_nanos": 1200 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data[0].parse, None);
+            assert_eq!(timings.data[0].part_1, Some(Duration::from_micros(74_100)));
+            assert_eq!(timings.data[0].part_2, Some(Duration::from_nanos(39)));
+            assert_eq!(timings.data[1].part_1, Some(Duration::from_nanos(1200)));
+            assert_eq!(timings.data[1].part_2, None);
+        }
+
+        #[test]
+        fn handles_json_years() {
+            let json = r#"{ "data": [{ "day": "01", "year": 2021, "part_1": null, "part_2": null, "total_nanos": 0 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.puzzle.year, 2021);
+            assert_eq!(timing.puzzle.day, day!(1));
+
+            let json = tinyjson::JsonValue::from(timings.clone())
+                .stringify()
+                .unwrap();
+            assert_eq!(Timings::try_from(json).unwrap().data[0].puzzle.year, 2021);
+        }
+
+        #[test]
+        fn handles_json_stats() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "2ms", "total_nanos": 3000000, "part_1_stats": { "samples": 10, "median_nanos": 1000000, "min_nanos": 900000, "max_nanos": 1500000, "std_dev_nanos": 1000, "p95_nanos": 1400000, "outliers": 1 }, "part_2_stats": null }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            let stats = timing.part_1_stats.unwrap();
+            assert_eq!(stats.samples, 10);
+            assert_eq!(stats.median, Duration::from_millis(1));
+            assert_eq!(stats.min, Duration::from_micros(900));
+            assert_eq!(stats.max, Duration::from_micros(1500));
+            assert_eq!(stats.std_dev, Duration::from_micros(1));
+            assert_eq!(stats.p95, Duration::from_micros(1400));
+            assert_eq!(stats.outliers, 1);
+            assert_eq!(timing.part_2_stats, None);
         }
 
         #[test]
@@ -280,47 +500,40 @@ mod tests {
             day,
             template::timings::{Timing, Timings},
         };
+        use std::time::Duration;
 
         #[test]
         fn handles_completed_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
-                    total_nanos: 3_000_000_000_f64,
+                    part_1: Some(Duration::from_millis(1)),
+                    part_2: Some(Duration::from_millis(2)),
+                    ..Timing::new(day!(1).into())
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1).into()), true);
         }
 
         #[test]
         fn handles_partial_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: None,
-                    total_nanos: 1_000_000_000_f64,
+                    part_1: Some(Duration::from_millis(1)),
+                    ..Timing::new(day!(1).into())
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1).into()), false);
         }
 
         #[test]
         fn handles_uncompleted_days() {
             let timings = Timings {
-                data: vec![Timing {
-                    day: day!(1),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0.0,
-                }],
+                data: vec![Timing::new(day!(1).into())],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1).into()), false);
         }
     }
 
@@ -328,7 +541,9 @@ mod tests {
         use crate::{
             day,
             template::timings::{Timing, Timings},
+            template::Memory,
         };
+        use std::time::Duration;
 
         use super::get_mock_timings;
 
@@ -336,19 +551,14 @@ mod tests {
         fn handles_disjunct_timings() {
             let timings = get_mock_timings();
             let other = Timings {
-                data: vec![Timing {
-                    day: day!(3),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0_f64,
-                }],
+                data: vec![Timing::new(day!(3).into())],
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
-            assert_eq!(merged.data[0].day, day!(1));
-            assert_eq!(merged.data[1].day, day!(2));
-            assert_eq!(merged.data[2].day, day!(3));
-            assert_eq!(merged.data[3].day, day!(4));
+            assert_eq!(merged.data[0].puzzle.day, day!(1));
+            assert_eq!(merged.data[1].puzzle.day, day!(2));
+            assert_eq!(merged.data[2].puzzle.day, day!(3));
+            assert_eq!(merged.data[3].puzzle.day, day!(4));
         }
 
         #[test]
@@ -356,20 +566,15 @@ mod tests {
             let timings = get_mock_timings();
 
             let other = Timings {
-                data: vec![Timing {
-                    day: day!(2),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0_f64,
-                }],
+                data: vec![Timing::new(day!(2).into())],
             };
             let merged = timings.merge(&other);
 
             assert_eq!(merged.data.len(), 3);
-            assert_eq!(merged.data[0].day, day!(1));
-            assert_eq!(merged.data[1].day, day!(2));
-            assert_eq!(merged.data[1].total_nanos, 0_f64);
-            assert_eq!(merged.data[2].day, day!(4));
+            assert_eq!(merged.data[0].puzzle.day, day!(1));
+            assert_eq!(merged.data[1].puzzle.day, day!(2));
+            assert_eq!(merged.data[1].total(), Duration::ZERO);
+            assert_eq!(merged.data[2].puzzle.day, day!(4));
         }
 
         #[test]
@@ -387,5 +592,55 @@ mod tests {
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 3);
         }
+
+        #[test]
+        fn keeps_memory_of_previous_timings() {
+            let memory = Memory {
+                total_bytes: 276,
+                total_blocks: 3,
+                max_bytes: 232,
+            };
+
+            let mut timings = get_mock_timings();
+            timings.data[1].part_2_memory = Some(memory);
+
+            let merged = timings.merge(&get_mock_timings());
+            assert_eq!(merged.data[1].part_1_memory, None);
+            assert_eq!(merged.data[1].part_2_memory, Some(memory));
+        }
+
+        #[test]
+        fn merges_memory_only() {
+            let memory = Memory {
+                total_bytes: 276,
+                total_blocks: 3,
+                max_bytes: 232,
+            };
+
+            let timings = get_mock_timings();
+            let other = Timings {
+                data: vec![
+                    Timing {
+                        part_1: Some(Duration::from_millis(300)),
+                        part_1_memory: Some(memory),
+                        ..Timing::new(day!(2).into())
+                    },
+                    Timing {
+                        part_1: Some(Duration::from_millis(1)),
+                        part_1_memory: Some(memory),
+                        ..Timing::new(day!(3).into())
+                    },
+                ],
+            };
+
+            let merged = timings.merge_memory(&other);
+            assert_eq!(merged.data.len(), 4);
+            assert_eq!(merged.data[1].part_1, Some(Duration::from_millis(30)));
+            assert_eq!(merged.data[1].total(), Duration::from_millis(70));
+            assert_eq!(merged.data[1].part_1_memory, Some(memory));
+            assert_eq!(merged.data[2].part_1, None);
+            assert_eq!(merged.data[2].total(), Duration::ZERO);
+            assert_eq!(merged.data[2].part_1_memory, Some(memory));
+        }
     }
 }